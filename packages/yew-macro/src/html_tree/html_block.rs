@@ -1,10 +1,10 @@
-use super::{HtmlIterable, HtmlNode, ToNodeIterator};
+use super::{HtmlIterable, HtmlNode, HtmlTree, ToNodeIterator};
 use crate::PeekValue;
 use proc_macro2::Delimiter;
 use quote::{quote, quote_spanned, ToTokens};
 use syn::buffer::Cursor;
 use syn::parse::{Parse, ParseStream};
-use syn::{braced, token};
+use syn::{braced, token, Lit, LitStr};
 
 pub struct HtmlBlock {
     pub content: BlockContent,
@@ -16,6 +16,30 @@ pub enum BlockContent {
     Iterable(Box<HtmlIterable>),
 }
 
+impl HtmlBlock {
+    /// Creates a block rendering `lit` as text
+    pub fn text(lit: LitStr) -> Self {
+        HtmlBlock {
+            brace: token::Brace { span: lit.span() },
+            content: BlockContent::Node(Box::new(HtmlNode::Literal(Box::new(Lit::Str(lit))))),
+        }
+    }
+
+    /// Returns the text of the block, if it contains a single literal
+    pub fn literal_text(&self) -> Option<LitStr> {
+        match &self.content {
+            BlockContent::Node(node) => node.literal_text(),
+            BlockContent::Iterable(_) => None,
+        }
+    }
+}
+
+impl From<HtmlBlock> for HtmlTree {
+    fn from(block: HtmlBlock) -> Self {
+        HtmlTree::Block(Box::new(block))
+    }
+}
+
 impl PeekValue<()> for HtmlBlock {
     fn peek(cursor: Cursor) -> Option<()> {
         cursor.group(Delimiter::Brace).map(|_| ())
@@ -3,7 +3,7 @@ use crate::props::{ClassesForm, ElementProps, Prop};
 use crate::stringify::{Stringify, Value};
 use crate::{non_capitalized_ascii, Peek, PeekValue};
 use boolinator::Boolinator;
-use proc_macro2::{Delimiter, TokenStream};
+use proc_macro2::{Delimiter, Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
 use syn::buffer::Cursor;
 use syn::parse::{Parse, ParseStream};
@@ -123,7 +123,8 @@ impl ToTokens for HtmlElement {
         let key = key
             .as_ref()
             .map(|attr| {
-                let value = attr.value.optimize_literals();
+                // Literals are not stringified here, so numeric keys keep their kind
+                let value = &attr.value;
                 quote_spanned! {value.span()=>
                     ::std::option::Option::Some(
                         ::std::convert::Into::<::yew::virtual_dom::Key>::into(#value)
@@ -166,7 +167,7 @@ impl ToTokens for HtmlElement {
                             }}),
                         },
                         expr => Value::Dynamic(quote_spanned! {expr.span()=>
-                            if #expr {
+                            if ::yew::utils::__is_boolean_attr_set(#expr) {
                                 ::std::option::Option::Some(
                                     ::yew::virtual_dom::AttrValue::Static(#key)
                                 )
@@ -298,57 +299,53 @@ impl ToTokens for HtmlElement {
             TagName::Lit(name) => {
                 let name_span = name.span();
                 let name = name.to_ascii_lowercase_string();
-                let node = match &*name {
+                let vtag = match &*name {
                     "input" => {
                         quote! {
-                            ::std::convert::Into::<::yew::virtual_dom::VNode>::into(
-                                ::yew::virtual_dom::VTag::__new_input(
-                                    #value,
-                                    #checked,
-                                    #node_ref,
-                                    #key,
-                                    #attributes,
-                                    #listeners,
-                                ),
+                            ::yew::virtual_dom::VTag::__new_input(
+                                #value,
+                                #checked,
+                                #node_ref,
+                                #key,
+                                #attributes,
+                                #listeners,
                             )
                         }
                     }
                     "textarea" => {
                         quote! {
-                            ::std::convert::Into::<::yew::virtual_dom::VNode>::into(
-                                ::yew::virtual_dom::VTag::__new_textarea(
-                                    #value,
-                                    #node_ref,
-                                    #key,
-                                    #attributes,
-                                    #listeners,
-                                ),
+                            ::yew::virtual_dom::VTag::__new_textarea(
+                                #value,
+                                #node_ref,
+                                #key,
+                                #attributes,
+                                #listeners,
                             )
                         }
                     }
                     _ => {
                         quote! {
-                            ::std::convert::Into::<::yew::virtual_dom::VNode>::into(
-                                ::yew::virtual_dom::VTag::__new_other(
-                                    ::std::borrow::Cow::<'static, ::std::primitive::str>::Borrowed(#name),
-                                    #node_ref,
-                                    #key,
-                                    #attributes,
-                                    #listeners,
-                                    #child_list,
-                                ),
+                            ::yew::virtual_dom::VTag::__new_other(
+                                ::std::borrow::Cow::<'static, ::std::primitive::str>::Borrowed(#name),
+                                #node_ref,
+                                #key,
+                                #attributes,
+                                #listeners,
+                                #child_list,
                             )
                         }
                     }
                 };
+                let set_source_location = set_source_location(name_span);
                 // the return value can be inlined without the braces when this is stable:
                 // https://github.com/rust-lang/rust/issues/15701
                 quote_spanned!{
                     name_span =>
                     {
-                        #[allow(clippy::redundant_clone, unused_braces)]
-                        let node = #node;
-                        node
+                        #[allow(clippy::redundant_clone, unused_braces, unused_mut)]
+                        let mut __yew_vtag = #vtag;
+                        #set_source_location
+                        ::std::convert::Into::<::yew::virtual_dom::VNode>::into(__yew_vtag)
                     }
                 }
             }
@@ -356,6 +353,7 @@ impl ToTokens for HtmlElement {
                 let vtag = Ident::new("__yew_vtag", name.span());
                 let expr = &name.expr;
                 let vtag_name = Ident::new("__yew_vtag_name", expr.span());
+                let set_source_location = set_source_location(name.span());
 
                 // handle special attribute value
                 let handle_value_attr = props.value.as_ref().map(|prop| {
@@ -442,6 +440,7 @@ impl ToTokens for HtmlElement {
                         }
                     }
 
+                    #set_source_location
                     ::std::convert::Into::<::yew::virtual_dom::VNode>::into(#vtag)
                 }}
             }
@@ -449,6 +448,14 @@ impl ToTokens for HtmlElement {
     }
 }
 
+/// Records the location of the tag at `span` on `__yew_vtag` in debug builds
+pub(super) fn set_source_location(span: Span) -> TokenStream {
+    quote_spanned! {span=>
+        #[cfg(debug_assertions)]
+        __yew_vtag.__macro_set_source_location();
+    }
+}
+
 fn wrap_attr_prop(prop: &Prop) -> TokenStream {
     let value = prop.value.optimize_literals();
     quote_spanned! {value.span()=>
@@ -5,7 +5,7 @@ use quote::{quote, quote_spanned, ToTokens};
 use syn::buffer::Cursor;
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
-use syn::Expr;
+use syn::{Expr, ExprLit, Lit};
 
 pub struct HtmlList {
     open: HtmlListOpen,
@@ -47,6 +47,10 @@ impl Parse for HtmlList {
 
         let close = input.parse::<HtmlListClose>()?;
 
+        if open.props.whitespace == Whitespace::Coalesce {
+            children.coalesce_text();
+        }
+
         Ok(Self {
             open,
             children,
@@ -75,10 +79,31 @@ impl ToTokens for HtmlList {
             quote! { #open #close }
         };
 
+        let set_node_ref = open.props.node_ref.as_ref().map(|node_ref| {
+            let node_ref = quote_spanned! {node_ref.span()=>
+                ::yew::html::IntoPropValue::<::yew::html::NodeRef>::into_prop_value(#node_ref)
+            };
+            quote_spanned! {spanned.span()=>
+                __yew_vlist.node_ref = ::std::option::Option::Some(#node_ref);
+            }
+        });
+        // Fragments are located by their opening tag
+        let set_source_location = quote_spanned! {open.to_spanned().span()=>
+            #[cfg(debug_assertions)]
+            __yew_vlist.__macro_set_source_location();
+        };
+        let vlist = quote_spanned! {spanned.span()=>
+            {
+                #[allow(unused_mut)]
+                let mut __yew_vlist = ::yew::virtual_dom::VList::with_children(#children, #key);
+                #set_node_ref
+                #set_source_location
+                __yew_vlist
+            }
+        };
+
         tokens.extend(quote_spanned! {spanned.span()=>
-            ::yew::virtual_dom::VNode::VList(
-                ::yew::virtual_dom::VList::with_children(#children, #key)
-            )
+            ::yew::virtual_dom::VNode::VList(#vlist)
         });
     }
 }
@@ -117,30 +142,79 @@ impl Parse for HtmlListOpen {
     }
 }
 
+/// How the text children of a fragment are emitted
+#[derive(PartialEq)]
+enum Whitespace {
+    /// Every text literal is its own text node
+    Preserve,
+    /// Adjacent text literals are joined into a single text node
+    Coalesce,
+}
+
+impl Whitespace {
+    fn from_expr(expr: &Expr) -> syn::Result<Self> {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) = expr
+        {
+            match s.value().as_str() {
+                "preserve" => return Ok(Self::Preserve),
+                "coalesce" => return Ok(Self::Coalesce),
+                _ => (),
+            }
+        }
+        Err(syn::Error::new_spanned(
+            expr,
+            "expected `\"preserve\"` or `\"coalesce\"`",
+        ))
+    }
+}
+
 struct HtmlListProps {
     key: Option<Expr>,
+    node_ref: Option<Expr>,
+    whitespace: Whitespace,
 }
 impl Parse for HtmlListProps {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let key = if input.is_empty() {
-            None
-        } else {
+        let mut key = None;
+        let mut node_ref = None;
+        let mut whitespace = None;
+        while !input.is_empty() {
             let prop: Prop = input.parse()?;
-            if !input.is_empty() {
-                return Err(input.error("only a single `key` prop is allowed on a fragment"));
-            }
-
-            if prop.label.to_ascii_lowercase_string() != "key" {
+            let dst = match prop.label.to_ascii_lowercase_string().as_str() {
+                "key" => &mut key,
+                "ref" => &mut node_ref,
+                "whitespace" => &mut whitespace,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        prop.label,
+                        "fragments only accept the `key`, `ref` and `whitespace` props",
+                    ))
+                }
+            };
+            if dst.is_some() {
                 return Err(syn::Error::new_spanned(
-                    prop.label,
-                    "fragments only accept the `key` prop",
+                    &prop.label,
+                    format!(
+                        "only a single `{}` prop is allowed on a fragment",
+                        prop.label
+                    ),
                 ));
             }
+            *dst = Some(prop.value);
+        }
 
-            Some(prop.value)
+        let whitespace = match &whitespace {
+            Some(expr) => Whitespace::from_expr(expr)?,
+            None => Whitespace::Preserve,
         };
 
-        Ok(Self { key })
+        Ok(Self {
+            key,
+            node_ref,
+            whitespace,
+        })
     }
 }
 
@@ -6,13 +6,23 @@ use quote::{quote_spanned, ToTokens};
 use syn::buffer::Cursor;
 use syn::parse::{Parse, ParseStream, Result};
 use syn::spanned::Spanned;
-use syn::{Expr, Lit};
+use syn::{Expr, Lit, LitStr};
 
 pub enum HtmlNode {
     Literal(Box<Lit>),
     Expression(Box<Expr>),
 }
 
+impl HtmlNode {
+    /// Returns the text of a literal, if it is known at compile time
+    pub fn literal_text(&self) -> Option<LitStr> {
+        match self {
+            HtmlNode::Literal(lit) => lit.try_into_lit(),
+            HtmlNode::Expression(_) => None,
+        }
+    }
+}
+
 impl Parse for HtmlNode {
     fn parse(input: ParseStream) -> Result<Self> {
         let node = if HtmlNode::peek(input.cursor()).is_some() {
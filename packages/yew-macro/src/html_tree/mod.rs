@@ -6,7 +6,7 @@ use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
 use syn::Token;
-use syn::{braced, token};
+use syn::{braced, token, LitStr};
 
 mod html_block;
 mod html_component;
@@ -195,6 +195,16 @@ pub trait ToNodeIterator {
     fn to_node_iterator_stream(&self) -> Option<TokenStream>;
 }
 
+impl HtmlTree {
+    /// Returns the text of a block containing a single literal
+    fn literal_text(&self) -> Option<LitStr> {
+        match self {
+            HtmlTree::Block(block) => block.literal_text(),
+            _ => None,
+        }
+    }
+}
+
 impl ToNodeIterator for HtmlTree {
     fn to_node_iterator_stream(&self) -> Option<TokenStream> {
         match self {
@@ -221,6 +231,26 @@ impl HtmlChildrenTree {
         self.0.is_empty()
     }
 
+    /// Join runs of adjacent literal children, so each run is rendered as a single text node.
+    pub fn coalesce_text(&mut self) {
+        let mut children = Vec::with_capacity(self.0.len());
+        let mut text: Option<LitStr> = None;
+        for child in std::mem::take(&mut self.0) {
+            match (child.literal_text(), &mut text) {
+                (Some(lit), Some(run)) => {
+                    *run = LitStr::new(&(run.value() + &lit.value()), run.span());
+                }
+                (Some(lit), None) => text = Some(lit),
+                (None, _) => {
+                    children.extend(text.take().map(HtmlBlock::text).map(HtmlTree::from));
+                    children.push(child);
+                }
+            }
+        }
+        children.extend(text.map(HtmlBlock::text).map(HtmlTree::from));
+        self.0 = children;
+    }
+
     // Check if each child represents a single node.
     // This is the case when no expressions are used.
     fn only_single_node_children(&self) -> bool {
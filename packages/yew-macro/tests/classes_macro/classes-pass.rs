@@ -55,6 +55,9 @@ fn compile_pass() {
         ::std::option::Option::None::<&'static ::std::primitive::str>,
     );
 
+    // conditional classes
+    ::yew::classes!(("one", true), ("two", false), (::std::vec!["three"], true));
+
     // mixed types
     {
         use ::std::borrow::ToOwned;
@@ -251,6 +251,21 @@ mod t12 {
     }
 }
 
+mod t12_callback {
+    #[derive(::std::clone::Clone, ::yew::Properties, ::std::cmp::PartialEq)]
+    pub struct Props {
+        #[prop_or_default]
+        onclick: ::yew::Callback<::std::primitive::u32>,
+    }
+
+    fn default_callback_prop_should_work() {
+        use ::yew::Properties;
+
+        Props::builder().build();
+        Props::builder().onclick(|_| ()).build();
+    }
+}
+
 #[deny(non_snake_case, dead_code)]
 mod t13 {
     #[derive(::std::cmp::PartialEq, ::yew::Properties)]
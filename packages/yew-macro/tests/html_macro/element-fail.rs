@@ -36,7 +36,6 @@ fn compile_fail() {
     html! { <input checked=1 /> };
     html! { <input checked={Some(false)} /> };
     html! { <input disabled=1 /> };
-    html! { <input disabled={Some(true)} /> };
     html! { <option selected=1 /> };
 
     // normal attribute type mismatch
@@ -41,6 +41,8 @@ fn compile_pass() {
         |_: ::yew::events::MouseEvent| (),
     );
     let parent_ref = <::yew::NodeRef as ::std::default::Default>::default();
+    let value = "value";
+    let name = "name";
 
     let dyn_tag =
         || <::std::string::String as ::std::convert::From<&::std::primitive::str>>::from("test");
@@ -81,6 +83,8 @@ fn compile_pass() {
             <img class={::yew::classes!("avatar", "hidden")} src="http://pic.com" />
             <img class="avatar hidden" />
             <button onclick={&onclick} {onclick} />
+            <input {value} {name} />
+            <input disabled={::std::option::Option::Some(true)} hidden={::std::option::Option::None} />
             <a href="http://google.com" />
             <custom-tag-a>
                 <custom-tag-b />
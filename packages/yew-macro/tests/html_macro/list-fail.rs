@@ -23,6 +23,8 @@ fn compile_fail() {
     html! { <key="first key" key="second key" /> };
     // invalid prop
     html! { <some_attr="test"></> };
+    // invalid whitespace mode
+    html! { <whitespace="trim"></> };
 }
 
 fn main() {}
@@ -49,6 +49,18 @@ fn main() {
         <key={::std::string::ToString::to_string("key")}>
         </>
     };
+    let node_ref = <::yew::html::NodeRef as ::std::default::Default>::default();
+    ::yew::html! {
+        <key="key" ref={node_ref}>
+        </>
+    };
+
+    ::yew::html! {
+        <whitespace="coalesce">
+            { "a" }{ " " }{ 1 }<b></b>{ 'c' }
+        </>
+    };
+    ::yew::html! { <whitespace="preserve">{ "a" }{ "b" }</> };
 
     let children = ::std::vec![
         ::yew::html! { <span>{ "Hello" }</span> },
@@ -3,7 +3,7 @@
 
 use std::ops::Deref;
 
-use crate::html::{BaseComponent, NodeRef, Scope, Scoped};
+use crate::html::{BaseComponent, Component, Context, Html, NodeRef, Properties, Scope, Scoped};
 use std::rc::Rc;
 use web_sys::Element;
 
@@ -27,14 +27,20 @@ where
         let app = Self {
             scope: Scope::new(None),
         };
-        app.scope
-            .mount_in_place(element, NodeRef::default(), NodeRef::default(), props);
+        app.scope.mount_in_place(
+            element.into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            props,
+        );
 
         app
     }
 
     /// Schedule the app for destruction
     pub fn destroy(mut self) {
+        #[cfg(feature = "render_hooks")]
+        feat_render_hooks::remove(self.scope.app_id);
         self.scope.destroy(false)
     }
 }
@@ -50,6 +56,121 @@ where
     }
 }
 
+#[cfg(feature = "render_hooks")]
+pub(crate) use feat_render_hooks::{render_commit, render_start};
+
+#[cfg(feature = "render_hooks")]
+mod feat_render_hooks {
+    use super::AppHandle;
+    use crate::html::{AnyScope, BaseComponent};
+    use crate::Callback;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct RenderHooks {
+        start: Option<Callback<()>>,
+        commit: Option<Callback<()>>,
+    }
+
+    thread_local! {
+        /// Hooks of each app, by the address of the state of its root component
+        static HOOKS: RefCell<HashMap<usize, RenderHooks>> = RefCell::new(HashMap::new());
+        /// Apps which started rendering since the scheduler last ran out of work
+        static RENDERING: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+    }
+
+    impl<COMP> AppHandle<COMP>
+    where
+        COMP: BaseComponent,
+    {
+        /// Sets a callback, which is called right before the components of this app start
+        /// applying new renders to the DOM.
+        ///
+        /// Renders scheduled together, e.g. of a component and its children, are reported once.
+        /// Together with [`on_render_commit`](Self::on_render_commit), this allows testing
+        /// utilities to wait for renders to finish. The callback is called while a component is
+        /// being rendered, so it must not access it.
+        #[cfg_attr(documenting, doc(cfg(feature = "render_hooks")))]
+        pub fn on_render_start(&self, callback: Callback<()>) {
+            self.with_hooks(|hooks| hooks.start = Some(callback));
+        }
+
+        /// Sets a callback, which is called once all components of this app applied their new
+        /// renders to the DOM and the scheduler ran out of work.
+        #[cfg_attr(documenting, doc(cfg(feature = "render_hooks")))]
+        pub fn on_render_commit(&self, callback: Callback<()>) {
+            self.with_hooks(|hooks| hooks.commit = Some(callback));
+        }
+
+        fn with_hooks(&self, f: impl FnOnce(&mut RenderHooks)) {
+            let app_id = self.scope.app_id;
+            HOOKS.with(|h| f(h.borrow_mut().entry(app_id).or_default()));
+        }
+    }
+
+    /// Removes the hooks of a destroyed app
+    pub(super) fn remove(app_id: usize) {
+        HOOKS.with(|h| h.borrow_mut().remove(&app_id));
+    }
+
+    fn emit(app_id: usize, hook: impl FnOnce(&RenderHooks) -> Option<Callback<()>>) {
+        // Not emitted while borrowed, so the callbacks can register hooks themselves
+        if let Some(callback) = HOOKS.with(|h| h.borrow().get(&app_id).and_then(hook)) {
+            callback.emit(());
+        }
+    }
+
+    /// Notifies the app of `scope`, that a component is about to apply a render, unless it
+    /// was already notified since the scheduler last ran out of work
+    pub(crate) fn render_start(scope: &AnyScope) {
+        let started = RENDERING.with(|r| {
+            let mut rendering = r.borrow_mut();
+            if rendering.contains(&scope.app_id) {
+                return false;
+            }
+            rendering.push(scope.app_id);
+            true
+        });
+        if started {
+            emit(scope.app_id, |hooks| hooks.start.clone());
+        }
+    }
+
+    /// Notifies the apps, which started rendering, that their renders were applied. Returns
+    /// `false`, if there were none.
+    pub(crate) fn render_commit() -> bool {
+        let rendering = RENDERING.with(|r| std::mem::take(&mut *r.borrow_mut()));
+        for &app_id in rendering.iter() {
+            emit(app_id, |hooks| hooks.commit.clone());
+        }
+        !rendering.is_empty()
+    }
+}
+
+/// Properties of [Detached]
+#[derive(Properties, PartialEq)]
+pub(crate) struct DetachedProps {
+    pub node: Html,
+}
+
+/// Root component rendering content outside of the application, see
+/// [`render_detached`](crate::render_detached).
+pub(crate) struct Detached;
+
+impl Component for Detached {
+    type Message = ();
+    type Properties = DetachedProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        ctx.props().node.clone()
+    }
+}
+
 /// Removes anything from the given element.
 fn clear_element(element: &Element) {
     while let Some(child) = element.last_child() {
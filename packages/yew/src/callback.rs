@@ -6,7 +6,10 @@
 
 use crate::html::ImplicitClone;
 use std::fmt;
+use std::ops::Deref;
 use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
 
 /// Universal callback wrapper.
 /// <aside class="warning">
@@ -83,6 +86,131 @@ impl<IN: 'static, OUT: 'static> Callback<IN, OUT> {
         };
         Callback::from(func)
     }
+
+    /// Creates a new callback from another callback and a function
+    /// That when emited will call that function and will only emit the original callback, if
+    /// the function returned `Some`.
+    ///
+    /// This is useful to drop invalid input before it becomes a component message, so that
+    /// no update is scheduled for it.
+    pub fn map_filter<F, T>(&self, func: F) -> Callback<T>
+    where
+        F: Fn(T) -> Option<IN> + 'static,
+    {
+        let this = self.clone();
+        let func = move |input| {
+            if let Some(output) = func(input) {
+                this.emit(output);
+            }
+        };
+        Callback::from(func)
+    }
+}
+
+impl<IN: 'static> Callback<IN> {
+    /// Creates a new callback, which emits this callback and then `other` with a clone of the
+    /// input.
+    ///
+    /// This combines handlers of the same event, e.g. one provided by a library with one's own.
+    pub fn then(&self, other: Callback<IN>) -> Callback<IN>
+    where
+        IN: Clone,
+    {
+        self.then_with(other, IN::clone)
+    }
+
+    /// Creates a new callback, which emits this callback and then `other` with a value produced
+    /// from the input by `func`.
+    ///
+    /// Unlike [`then`](Self::then), this does not require the input to be [`Clone`]. `func` is
+    /// called before this callback takes the input.
+    pub fn then_with<F, T>(&self, other: Callback<T>, func: F) -> Callback<IN>
+    where
+        F: Fn(&IN) -> T + 'static,
+        T: 'static,
+    {
+        let this = self.clone();
+        let func = move |input| {
+            let value = func(&input);
+            this.emit(input);
+            other.emit(value);
+        };
+        Callback::from(func)
+    }
+}
+
+impl<IN: JsCast + 'static> Callback<IN> {
+    /// Creates a JavaScript function, which emits this callback with its first argument, for
+    /// passing to JavaScript APIs.
+    ///
+    /// The function stays callable for as long as the returned [`JsCallback`] is alive. Store it
+    /// in the component, so it is dropped on teardown; calling the function from JavaScript
+    /// afterwards throws instead of emitting the callback. Arguments which are not an `IN` are
+    /// ignored with a warning.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use yew::prelude::*;
+    /// use yew::callback::JsCallback;
+    /// use wasm_bindgen::JsValue;
+    ///
+    /// struct Timeout {
+    ///     on_timeout: JsCallback,
+    /// }
+    ///
+    /// impl Component for Timeout {
+    ///     type Message = ();
+    ///     type Properties = ();
+    ///
+    ///     fn create(ctx: &Context<Self>) -> Self {
+    ///         let on_timeout = ctx.link().callback(|_: JsValue| ()).into_js();
+    ///         gloo_utils::window()
+    ///             .set_timeout_with_callback_and_timeout_and_arguments_0(&on_timeout, 1000)
+    ///             .unwrap();
+    ///         Self { on_timeout }
+    ///     }
+    ///
+    ///     fn view(&self, _ctx: &Context<Self>) -> Html {
+    ///         html! {}
+    ///     }
+    /// }
+    /// ```
+    pub fn into_js(&self) -> JsCallback {
+        let this = self.clone();
+        let closure = Closure::wrap(Box::new(move |value: JsValue| match value.dyn_into() {
+            Ok(value) => this.emit(value),
+            Err(value) => gloo::console::warn!(
+                format!(
+                    "ignoring JavaScript callback argument, which is not a {}:",
+                    std::any::type_name::<IN>()
+                ),
+                value
+            ),
+        }) as Box<dyn Fn(JsValue)>);
+        JsCallback { closure }
+    }
+}
+
+/// A JavaScript function created by [`Callback::into_js`].
+///
+/// Dereferences to the [`js_sys::Function`], which is released when this is dropped.
+pub struct JsCallback {
+    closure: Closure<dyn Fn(JsValue)>,
+}
+
+impl Deref for JsCallback {
+    type Target = js_sys::Function;
+
+    fn deref(&self) -> &Self::Target {
+        self.closure.as_ref().unchecked_ref()
+    }
+}
+
+impl fmt::Debug for JsCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JsCallback<_>")
+    }
 }
 
 impl<IN, OUT> ImplicitClone for Callback<IN, OUT> {}
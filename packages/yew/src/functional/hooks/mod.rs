@@ -1,13 +1,19 @@
+mod use_after_paint;
+mod use_callback;
 mod use_context;
 mod use_effect;
 mod use_memo;
+mod use_node_count_delta;
 mod use_reducer;
 mod use_ref;
 mod use_state;
 
+pub use use_after_paint::*;
+pub use use_callback::*;
 pub use use_context::*;
 pub use use_effect::*;
 pub use use_memo::*;
+pub use use_node_count_delta::*;
 pub use use_reducer::*;
 pub use use_ref::*;
 pub use use_state::*;
@@ -0,0 +1,76 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+use crate::functional::{hook, use_effect};
+
+/// Requests `f` to be called once the next frame has been painted.
+///
+/// `requestAnimationFrame` callbacks run right before the browser paints, so the callback is
+/// requested from within the next animation frame. The returned function cancels the request.
+fn request_after_paint(f: impl FnOnce() + 'static) -> impl FnOnce() {
+    let id = Rc::new(Cell::new(0));
+    let after_paint: Rc<RefCell<Option<Closure<dyn FnMut()>>>> = Rc::default();
+
+    let before_paint = Closure::once({
+        let id = id.clone();
+        let after_paint = after_paint.clone();
+        move || {
+            let callback = Closure::once(f);
+            id.set(
+                gloo_utils::window()
+                    .request_animation_frame(callback.as_ref().unchecked_ref())
+                    .expect("failed to request animation frame"),
+            );
+            *after_paint.borrow_mut() = Some(callback);
+        }
+    });
+    id.set(
+        gloo_utils::window()
+            .request_animation_frame(before_paint.as_ref().unchecked_ref())
+            .expect("failed to request animation frame"),
+    );
+
+    move || {
+        // Cancelling a frame which already ran has no effect
+        let _ = gloo_utils::window().cancel_animation_frame(id.get());
+        drop(before_paint);
+        drop(after_paint);
+    }
+}
+
+/// This hook is used to run code after the browser has painted the result of a render.
+///
+/// Unlike [`use_effect`](super::use_effect()), which runs before the browser updates the page,
+/// the callback can observe the painted state, e.g. for layout measurements. It is scheduled
+/// with `requestAnimationFrame` after every render and cancelled if the component re-renders or
+/// is destroyed before it ran.
+///
+/// # Example
+/// ```rust
+/// # use yew::prelude::*;
+/// #
+/// #[function_component(UseAfterPaint)]
+/// fn after_paint() -> Html {
+///     let node_ref = use_node_ref();
+///     {
+///         let node_ref = node_ref.clone();
+///         use_after_paint(move || {
+///             if let Some(element) = node_ref.cast::<web_sys::Element>() {
+///                 let _height = element.client_height();
+///             }
+///         });
+///     }
+///
+///     html! { <div ref={node_ref}>{ "Measure me" }</div> }
+/// }
+/// ```
+#[hook]
+pub fn use_after_paint<F>(f: F)
+where
+    F: FnOnce() + 'static,
+{
+    use_effect(move || request_after_paint(f));
+}
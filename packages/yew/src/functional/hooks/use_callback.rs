@@ -0,0 +1,57 @@
+use std::rc::Rc;
+
+use crate::callback::Callback;
+use crate::functional::{hook, use_memo};
+
+/// Get a memoized [`Callback`]
+///
+/// The callback keeps its identity, and therefore compares equal to its previous value, until
+/// the provided dependencies change. This allows passing it as a property to components without
+/// causing them to re-render every time the callback is recreated.
+///
+/// # Example
+/// ```rust
+/// # use yew::prelude::*;
+/// #
+/// #[derive(Properties, PartialEq)]
+/// pub struct Props {
+///     pub onclick: Callback<MouseEvent>,
+/// }
+///
+/// #[function_component(MyButton)]
+/// fn my_button(props: &Props) -> Html {
+///     html! { <button onclick={props.onclick.clone()}>{ "Click" }</button> }
+/// }
+///
+/// #[function_component(UseCallback)]
+/// fn callback() -> Html {
+///     let counter = use_state(|| 0);
+///     // `MyButton` is only re-rendered when `counter` changes
+///     let onclick = {
+///         let counter = counter.clone();
+///         use_callback(move |_, value| counter.set(*value + 1), *counter)
+///     };
+///
+///     html! { <MyButton {onclick} /> }
+/// }
+/// ```
+#[hook]
+pub fn use_callback<IN, OUT, F, D>(f: F, deps: D) -> Callback<IN, OUT>
+where
+    IN: 'static,
+    OUT: 'static,
+    F: Fn(IN, &D) -> OUT + 'static,
+    D: PartialEq + 'static,
+{
+    let deps = Rc::new(deps);
+
+    (*use_memo(
+        move |deps| {
+            let deps = deps.clone();
+            let f = move |value: IN| f(value, deps.as_ref());
+            Callback::from(f)
+        },
+        deps,
+    ))
+    .clone()
+}
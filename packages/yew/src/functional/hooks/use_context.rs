@@ -1,6 +1,7 @@
 use crate::callback::Callback;
 use crate::context::ContextHandle;
-use crate::functional::{hook, use_component_scope, use_memo, use_state};
+use crate::functional::{hook, use_component_scope, use_memo, use_mut_ref, use_state};
+use std::rc::Rc;
 
 /// Hook for consuming context values in function components.
 /// The context of the type passed as `T` is returned. If there is no such context in scope, `None` is returned.
@@ -55,3 +56,89 @@ pub fn use_context<T: Clone + PartialEq + 'static>() -> Option<T> {
         .clone()
         .or_else(move || state.context.as_ref().map(|m| m.0.clone()))
 }
+
+/// Hook for consuming a part of a context value in function components.
+///
+/// Unlike [`use_context`], the component does not re-render on every change of the context, but
+/// only when the part of the value returned by `selector` changes. This avoids re-rendering
+/// components, which only use a single field of a large context, like the state of an app.
+/// If there is no context of type `T` in scope, `None` is returned.
+///
+/// # Example
+/// ```rust
+/// # use yew::prelude::*;
+/// # use std::rc::Rc;
+///
+/// # #[derive(Clone, Debug, PartialEq)]
+/// # struct AppState {
+/// #    user_name: String,
+/// #    notifications: Vec<String>,
+/// # }
+/// #[function_component(UserName)]
+/// pub fn user_name() -> Html {
+///     // Not re-rendered when notifications arrive
+///     let user_name = use_context_selector(|state: &Rc<AppState>| state.user_name.clone())
+///         .expect("no ctx found");
+///
+///     html! { <span>{ user_name }</span> }
+/// }
+/// ```
+#[hook]
+pub fn use_context_selector<T, S, F>(selector: F) -> Option<S>
+where
+    T: Clone + PartialEq + 'static,
+    S: Clone + PartialEq + 'static,
+    F: Fn(&T) -> S + 'static,
+{
+    struct Selection<T, S> {
+        value: Option<T>,
+        selected: Option<S>,
+        selector: Rc<dyn Fn(&T) -> S>,
+    }
+
+    let scope = use_component_scope();
+
+    // The selector may capture props, so the latest one is used for context changes
+    let selector: Rc<dyn Fn(&T) -> S> = Rc::new(selector);
+    let selection = use_mut_ref(|| Selection {
+        value: None,
+        selected: None,
+        selector: selector.clone(),
+    });
+    selection.borrow_mut().selector = selector;
+
+    let rerender = use_state(|| ());
+    let _handle = {
+        let selection = selection.clone();
+        let rerender = rerender.setter();
+        use_memo(
+            move |_| -> Option<ContextHandle<T>> {
+                let on_change = {
+                    let selection = selection.clone();
+                    Callback::from(move |value: T| {
+                        let mut selection = selection.borrow_mut();
+                        let selected = (selection.selector)(&value);
+                        selection.value = Some(value);
+                        if selection.selected.as_ref() != Some(&selected) {
+                            selection.selected = Some(selected);
+                            drop(selection);
+                            rerender.set(());
+                        }
+                    })
+                };
+                let (value, handle) = scope.context::<T>(on_change)?;
+                selection.borrow_mut().value = Some(value);
+                Some(handle)
+            },
+            (),
+        )
+    };
+
+    let mut selection = selection.borrow_mut();
+    let selected = selection
+        .value
+        .as_ref()
+        .map(|value| (selection.selector)(value));
+    selection.selected = selected.clone();
+    selected
+}
@@ -0,0 +1,72 @@
+use crate::functional::{hook, use_component_scope};
+use crate::html::RenderedRoot;
+use crate::scheduler::Shared;
+use std::fmt;
+
+/// Handle returned by [`use_node_count_delta`]
+#[derive(Clone)]
+pub struct UseNodeCountDeltaHandle {
+    rendered_root: Shared<Option<RenderedRoot>>,
+}
+
+impl UseNodeCountDeltaHandle {
+    /// Returns the net change in the number of DOM nodes rendered at the top level of the
+    /// component caused by its last render.
+    ///
+    /// The value is updated right before effects run, so it describes the current render when
+    /// read from an effect and the previous render when read while rendering.
+    pub fn get(&self) -> isize {
+        self.rendered_root
+            .borrow()
+            .as_ref()
+            .map_or(0, |root| root.node_count_delta)
+    }
+}
+
+impl fmt::Debug for UseNodeCountDeltaHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UseNodeCountDeltaHandle")
+            .field("delta", &self.get())
+            .finish()
+    }
+}
+
+/// This hook is used to learn whether a render added or removed DOM nodes.
+///
+/// Containers doing imperative layout, like a masonry grid, can use this to skip re-measuring
+/// their children, when the number of nodes did not change. Nodes of nested fragments and
+/// components are counted, while the placeholder of an empty fragment is not.
+///
+/// # Example
+/// ```rust
+/// # use yew::prelude::*;
+/// #
+/// # #[derive(Properties, PartialEq)]
+/// # struct Props {
+/// #     children: Children,
+/// # }
+/// #
+/// #[function_component(Masonry)]
+/// fn masonry(props: &Props) -> Html {
+///     let delta = use_node_count_delta();
+///     {
+///         let delta = delta.clone();
+///         use_effect(move || {
+///             if delta.get() != 0 {
+///                 // re-measure the children
+///             }
+///             || {}
+///         });
+///     }
+///
+///     html! { <>{ for props.children.iter() }</> }
+/// }
+/// ```
+#[hook]
+pub fn use_node_count_delta() -> UseNodeCountDeltaHandle {
+    let scope = use_component_scope();
+
+    UseNodeCountDeltaHandle {
+        rendered_root: scope.rendered_root,
+    }
+}
@@ -9,10 +9,20 @@ struct UseStateReducer<T> {
     value: T,
 }
 
+enum UseStateAction<T> {
+    Set(T),
+    SetWith(Box<dyn FnOnce(&T) -> T>),
+}
+
 impl<T> Reducible for UseStateReducer<T> {
-    type Action = T;
+    type Action = UseStateAction<T>;
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
-        Rc::new(Self { value: action })
+        let value = match action {
+            UseStateAction::Set(value) => value,
+            UseStateAction::SetWith(f) => f(&self.value),
+        };
+
+        Rc::new(Self { value })
     }
 }
 
@@ -93,7 +103,20 @@ impl<T: fmt::Debug> fmt::Debug for UseStateHandle<T> {
 impl<T> UseStateHandle<T> {
     /// Replaces the value
     pub fn set(&self, value: T) {
-        self.inner.dispatch(value)
+        self.inner.dispatch(UseStateAction::Set(value))
+    }
+
+    /// Replaces the value with the result of `f` applied to the latest state.
+    ///
+    /// Unlike computing the next value from the handle, this doesn't miss updates made since the
+    /// handle was created, e.g. when several updates are dispatched before the next render.
+    ///
+    /// `f` must not update the same state itself.
+    pub fn set_with<F>(&self, f: F)
+    where
+        F: FnOnce(&T) -> T + 'static,
+    {
+        self.inner.dispatch(UseStateAction::SetWith(Box::new(f)))
     }
 
     /// Returns the setter of current state.
@@ -160,6 +183,16 @@ impl<T> PartialEq for UseStateSetter<T> {
 impl<T> UseStateSetter<T> {
     /// Replaces the value
     pub fn set(&self, value: T) {
-        self.inner.dispatch(value)
+        self.inner.dispatch(UseStateAction::Set(value))
+    }
+
+    /// Replaces the value with the result of `f` applied to the latest state.
+    ///
+    /// See [`UseStateHandle::set_with`].
+    pub fn set_with<F>(&self, f: F)
+    where
+        F: FnOnce(&T) -> T + 'static,
+    {
+        self.inner.dispatch(UseStateAction::SetWith(Box::new(f)))
     }
 }
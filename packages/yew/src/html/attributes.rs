@@ -0,0 +1,124 @@
+//! Typed values of enumerated attributes.
+
+use std::fmt;
+
+macro_rules! enumerated_attribute {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $( $(#[$variant_meta:meta])* $variant:ident => $value:literal, )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $( $(#[$variant_meta])* $variant, )*
+        }
+
+        impl $name {
+            /// Returns the value of the attribute.
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $( Self::$variant => $value, )*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+enumerated_attribute! {
+    /// Value of the
+    /// [`inputmode`](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/inputmode)
+    /// attribute, hinting at the virtual keyboard to show for editing an element.
+    ///
+    /// # Example
+    /// ```
+    /// # use yew::prelude::*;
+    /// use yew::html::InputMode;
+    /// # fn test() -> Html {
+    /// html! { <input inputmode={InputMode::Numeric} /> }
+    /// # }
+    /// ```
+    InputMode {
+        /// No virtual keyboard, e.g. for elements providing their own keyboard input
+        None => "none",
+        /// The standard keyboard of the user's locale
+        Text => "text",
+        /// Digits and the decimal separator of the user's locale
+        Decimal => "decimal",
+        /// Digits only
+        Numeric => "numeric",
+        /// A telephone keypad
+        Tel => "tel",
+        /// A keyboard optimized for search input
+        Search => "search",
+        /// A keyboard optimized for entering email addresses
+        Email => "email",
+        /// A keyboard optimized for entering URLs
+        Url => "url",
+    }
+}
+
+enumerated_attribute! {
+    /// Value of the
+    /// [`autocapitalize`](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/autocapitalize)
+    /// attribute, controlling whether entered text is capitalized automatically.
+    AutoCapitalize {
+        /// No text is capitalized automatically
+        Off => "off",
+        /// The first letter of each sentence
+        Sentences => "sentences",
+        /// The first letter of each word
+        Words => "words",
+        /// All letters
+        Characters => "characters",
+    }
+}
+
+enumerated_attribute! {
+    /// Value of the
+    /// [`enterkeyhint`](https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/enterkeyhint)
+    /// attribute, choosing the label of the enter key of virtual keyboards.
+    EnterKeyHint {
+        /// Inserts a new line
+        Enter => "enter",
+        /// Closes the input method
+        Done => "done",
+        /// Navigates to the target of the entered text
+        Go => "go",
+        /// Moves to the next field
+        Next => "next",
+        /// Moves to the previous field
+        Previous => "previous",
+        /// Searches for the entered text
+        Search => "search",
+        /// Sends the entered text
+        Send => "send",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html::IntoPropValue;
+    use crate::virtual_dom::AttrValue;
+
+    #[test]
+    fn attribute_values() {
+        assert_eq!(InputMode::None.as_str(), "none");
+        assert_eq!(InputMode::Decimal.to_string(), "decimal");
+        assert_eq!(AutoCapitalize::Characters.as_str(), "characters");
+        assert_eq!(EnterKeyHint::Previous.as_str(), "previous");
+
+        let value: Option<AttrValue> = InputMode::Email.into_prop_value();
+        assert_eq!(value.as_deref(), Some("email"));
+        let value: Option<AttrValue> = None::<EnterKeyHint>.into_prop_value();
+        assert_eq!(value, None);
+    }
+}
@@ -180,6 +180,17 @@ impl<T: Into<Classes> + Clone> From<&Option<T>> for Classes {
     }
 }
 
+/// Includes the classes only if the condition is `true`, e.g. `classes!("btn", ("active", active))`.
+impl<T: Into<Classes>> From<(T, bool)> for Classes {
+    fn from((t, condition): (T, bool)) -> Self {
+        if condition {
+            t.into()
+        } else {
+            Self::default()
+        }
+    }
+}
+
 impl<T: Into<Classes>> From<Vec<T>> for Classes {
     fn from(t: Vec<T>) -> Self {
         Self::from_iter(t)
@@ -278,6 +289,16 @@ mod tests {
         assert!(subject.contains("bar"));
     }
 
+    #[test]
+    fn conditional_classes_follow_condition() {
+        let mut subject = Classes::from(("foo", true));
+        subject.push(("bar", false));
+        subject.push((vec!["baz", "qux"], true));
+        assert!(subject.contains("foo"));
+        assert!(!subject.contains("bar"));
+        assert_eq!(subject.to_string(), "foo baz qux");
+    }
+
     #[test]
     fn can_be_collected() {
         let classes = vec!["foo", "bar"];
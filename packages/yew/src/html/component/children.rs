@@ -1,7 +1,7 @@
 //! Component children module
 
 use crate::html::Html;
-use crate::virtual_dom::{VChild, VNode};
+use crate::virtual_dom::{Key, VChild, VList, VNode};
 use std::fmt;
 
 /// A type used for accepting children elements in Component::Properties.
@@ -186,6 +186,62 @@ where
     }
 }
 
+impl ChildrenRenderer<Html> {
+    /// Wraps each child with `f` and returns the results as a fragment.
+    ///
+    /// `f` receives the key of the original child along with the child itself. The key of the
+    /// original child is carried over to the node returned by `f`, unless it already has a key
+    /// of its own. This keeps keyed diffing of the wrapped children working, so that reordering
+    /// the children moves their wrappers instead of recreating them.
+    ///
+    /// # Example
+    /// ```
+    ///# use yew::{html, Children, Html};
+    ///# fn view(children: &Children) -> Html {
+    /// children.map_with_key(|_key, child| html! {
+    ///     <li class="item">{ child }</li>
+    /// })
+    ///# }
+    /// ```
+    pub fn map_with_key<F>(&self, mut f: F) -> Html
+    where
+        F: FnMut(Option<Key>, Html) -> Html,
+    {
+        let children = self
+            .iter()
+            .map(|child| {
+                let key = child.key();
+                let mut wrapped = f(key.clone(), child);
+                if !wrapped.has_key() {
+                    if let Some(key) = key {
+                        match &mut wrapped {
+                            VNode::VTag(vtag) => vtag.key = Some(key),
+                            VNode::VComp(vcomp) => vcomp.key = Some(key),
+                            VNode::VList(vlist) => vlist.key = Some(key),
+                            VNode::VSuspense(vsuspense) => vsuspense.key = Some(key),
+                            VNode::VMemo(_)
+                            | VNode::VText(_)
+                            | VNode::VRef(_)
+                            | VNode::VPortal(_)
+                            | VNode::VNothing(_) => (),
+                        }
+                    }
+                }
+                wrapped
+            })
+            .collect();
+
+        VNode::VList(VList::with_children(children, None))
+    }
+}
+
+impl From<ChildrenRenderer<Html>> for VList {
+    /// Creates a fragment of the children, which is diffed by key if all of them are keyed.
+    fn from(children: ChildrenRenderer<Html>) -> Self {
+        VList::with_children(children.children, None)
+    }
+}
+
 impl<T> Default for ChildrenRenderer<T> {
     fn default() -> Self {
         Self {
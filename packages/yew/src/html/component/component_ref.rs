@@ -0,0 +1,137 @@
+//! This module contains [ComponentRef], a reference to a mounted component.
+
+use super::{BaseComponent, Scope};
+use crate::html::NodeRef;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A reference to a mounted component, the component analog of [`NodeRef`].
+///
+/// Pass it as `ref` of a component in `html!`. Once the component is mounted, the ref provides
+/// its [`Scope`], e.g. to trigger imperative actions like focusing an input inside the component.
+/// Such actions are sent as messages instead of calling methods on the component directly, so the
+/// component is never aliased while it is rendered or updated.
+///
+/// # Example
+/// ```
+/// # use yew::prelude::*;
+/// # use yew::html::ComponentRef;
+/// pub struct Field;
+///
+/// pub enum FieldMsg {
+///     Focus,
+/// }
+///
+/// impl Component for Field {
+///     type Message = FieldMsg;
+///     type Properties = ();
+/// #   fn create(_ctx: &Context<Self>) -> Self {
+/// #       Self
+/// #   }
+/// #   fn view(&self, _ctx: &Context<Self>) -> Html {
+/// #       html! {}
+/// #   }
+///     // ...
+/// }
+///
+/// # fn view(field: &ComponentRef<Field>) -> Html {
+/// let onclick = {
+///     let field = field.clone();
+///     Callback::from(move |_| field.send_message(FieldMsg::Focus))
+/// };
+/// html! {
+///     <>
+///         <Field ref={field.clone()} />
+///         <button {onclick}>{ "Edit" }</button>
+///     </>
+/// }
+/// # }
+/// ```
+pub struct ComponentRef<COMP: BaseComponent> {
+    scope: Rc<RefCell<Option<Scope<COMP>>>>,
+    node_ref: NodeRef,
+}
+
+impl<COMP: BaseComponent> ComponentRef<COMP> {
+    /// Returns the scope of the referenced component, if it is mounted.
+    pub fn get(&self) -> Option<Scope<COMP>> {
+        self.scope
+            .borrow()
+            .as_ref()
+            .filter(|scope| scope.is_mounted())
+            .cloned()
+    }
+
+    /// Sends a message to the referenced component.
+    ///
+    /// Returns `false`, if the component is not mounted and the message was dropped.
+    pub fn send_message(&self, msg: impl Into<COMP::Message>) -> bool {
+        match self.get() {
+            Some(scope) => {
+                scope.send_message(msg);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the reference to the first node rendered by the component.
+    pub fn node_ref(&self) -> &NodeRef {
+        &self.node_ref
+    }
+
+    /// Links the ref to a newly mounted component
+    pub(crate) fn set(&self, scope: Scope<COMP>) {
+        *self.scope.borrow_mut() = Some(scope);
+    }
+}
+
+impl<COMP: BaseComponent> Default for ComponentRef<COMP> {
+    fn default() -> Self {
+        Self {
+            scope: Rc::default(),
+            node_ref: NodeRef::default(),
+        }
+    }
+}
+
+impl<COMP: BaseComponent> Clone for ComponentRef<COMP> {
+    fn clone(&self) -> Self {
+        Self {
+            scope: self.scope.clone(),
+            node_ref: self.node_ref.clone(),
+        }
+    }
+}
+
+impl<COMP: BaseComponent> PartialEq for ComponentRef<COMP> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+impl<COMP: BaseComponent> fmt::Debug for ComponentRef<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ComponentRef {{ mounted: {} }}", self.get().is_some())
+    }
+}
+
+/// Values accepted as `ref` of a component: a [`NodeRef`] to its first node or a
+/// [`ComponentRef`] to the component itself.
+pub trait IntoComponentRef<COMP: BaseComponent> {
+    /// Splits the value into the reference to the first node and the component reference
+    fn into_refs(self) -> (NodeRef, Option<ComponentRef<COMP>>);
+}
+
+impl<COMP: BaseComponent> IntoComponentRef<COMP> for NodeRef {
+    fn into_refs(self) -> (NodeRef, Option<ComponentRef<COMP>>) {
+        (self, None)
+    }
+}
+
+impl<COMP: BaseComponent> IntoComponentRef<COMP> for ComponentRef<COMP> {
+    fn into_refs(self) -> (NodeRef, Option<ComponentRef<COMP>>) {
+        (self.node_ref.clone(), Some(self))
+    }
+}
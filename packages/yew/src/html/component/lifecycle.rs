@@ -1,6 +1,6 @@
 //! Component lifecycle module
 
-use super::{AnyScope, BaseComponent, Scope};
+use super::{scope::RenderedRoot, AnyScope, BaseComponent, Scope};
 use crate::html::{RenderError, RenderResult};
 use crate::scheduler::{self, Runnable, Shared};
 use crate::suspense::{Suspense, Suspension};
@@ -11,13 +11,20 @@ use crate::{Context, NodeRef};
 use futures::channel::oneshot;
 use std::any::Any;
 use std::rc::Rc;
-use web_sys::Element;
+use web_sys::Node;
+
+#[cfg(test)]
+thread_local! {
+    /// Number of times props were compared field by field on an update
+    static PROPS_COMPARISONS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
 
 pub(crate) struct CompStateInner<COMP>
 where
     COMP: BaseComponent,
 {
-    pub(crate) component: COMP,
+    /// The component, or the fallback rendered in its place if it failed to be created.
+    pub(crate) component: Result<COMP, VNode>,
     pub(crate) context: Context<COMP>,
 }
 
@@ -45,15 +52,22 @@ where
     COMP: BaseComponent,
 {
     fn view(&self) -> RenderResult<VNode> {
-        self.component.view(&self.context)
+        match &self.component {
+            Ok(component) => component.view(&self.context),
+            Err(fallback) => Ok(fallback.clone()),
+        }
     }
 
     fn rendered(&mut self, first_render: bool) {
-        self.component.rendered(&self.context, first_render)
+        if let Ok(component) = &mut self.component {
+            component.rendered(&self.context, first_render)
+        }
     }
 
     fn destroy(&mut self) {
-        self.component.destroy(&self.context);
+        if let Ok(component) = &mut self.component {
+            component.destroy(&self.context);
+        }
     }
 
     fn any_scope(&self) -> AnyScope {
@@ -61,14 +75,13 @@ where
     }
 
     fn flush_messages(&mut self) -> bool {
-        self.context
-            .link()
-            .pending_messages
-            .drain()
-            .into_iter()
-            .fold(false, |acc, msg| {
-                self.component.update(&self.context, msg) || acc
-            })
+        let messages = self.context.link().pending_messages.drain();
+        match &mut self.component {
+            Ok(component) => messages.into_iter().fold(false, |acc, msg| {
+                component.update(&self.context, msg) || acc
+            }),
+            Err(_) => false,
+        }
     }
 
     fn props_changed(&mut self, props: Rc<dyn Any>) -> bool {
@@ -77,9 +90,19 @@ where
             _ => return false,
         };
 
+        // Identical props share storage, so there is no need to compare them field by field.
+        // Props equal to the current ones are dropped and the existing allocation is kept.
+        if Rc::ptr_eq(&self.context.props, &props) {
+            return false;
+        }
+        #[cfg(test)]
+        PROPS_COMPARISONS.with(|c| c.set(c.get() + 1));
         if self.context.props != props {
-            self.context.props = Rc::clone(&props);
-            self.component.changed(&self.context)
+            self.context.props = props;
+            match &mut self.component {
+                Ok(component) => component.changed(&self.context),
+                Err(_) => false,
+            }
         } else {
             false
         }
@@ -99,13 +122,14 @@ pub(crate) struct ComponentState {
     pub(crate) root_node: VNode,
 
     /// When a component has no parent, it means that it should not be rendered.
-    parent: Option<Element>,
+    parent: Option<Node>,
 
     next_sibling: NodeRef,
     node_ref: NodeRef,
     has_rendered: bool,
 
     suspension: Option<Suspension>,
+    rendered_root: Shared<Option<RenderedRoot>>,
 
     #[cfg(feature = "ssr")]
     html_sender: Option<oneshot::Sender<VNode>>,
@@ -117,7 +141,7 @@ pub(crate) struct ComponentState {
 
 impl ComponentState {
     pub(crate) fn new<COMP: BaseComponent>(
-        parent: Option<Element>,
+        parent: Option<Node>,
         next_sibling: NodeRef,
         root_node: VNode,
         node_ref: NodeRef,
@@ -127,10 +151,11 @@ impl ComponentState {
     ) -> Self {
         #[cfg(debug_assertions)]
         let vcomp_id = scope.vcomp_id;
+        let rendered_root = scope.rendered_root.clone();
         let context = Context { scope, props };
 
         let inner = Box::new(CompStateInner {
-            component: COMP::create(&context),
+            component: COMP::try_create(&context),
             context,
         });
 
@@ -141,6 +166,7 @@ impl ComponentState {
             next_sibling,
             node_ref,
             suspension: None,
+            rendered_root,
             has_rendered: false,
 
             #[cfg(feature = "ssr")]
@@ -153,7 +179,7 @@ impl ComponentState {
 }
 
 pub(crate) struct CreateRunner<COMP: BaseComponent> {
-    pub(crate) parent: Option<Element>,
+    pub(crate) parent: Option<Node>,
     pub(crate) next_sibling: NodeRef,
     pub(crate) placeholder: VNode,
     pub(crate) node_ref: NodeRef,
@@ -190,7 +216,7 @@ pub(crate) enum UpdateEvent {
     /// Wraps properties, node ref, and next sibling for a component.
     Properties(Rc<dyn Any>, NodeRef, NodeRef),
     /// Shift Scope.
-    Shift(Element, NodeRef),
+    Shift(Node, NodeRef),
 }
 
 pub(crate) struct UpdateRunner {
@@ -257,6 +283,7 @@ impl Runnable for DestroyRunner {
             crate::virtual_dom::vcomp::log_event(state.vcomp_id, "destroy");
 
             state.inner.destroy();
+            *state.rendered_root.borrow_mut() = None;
 
             if let Some(ref m) = state.parent {
                 state.root_node.detach(m, self.parent_to_detach);
@@ -300,6 +327,8 @@ impl Runnable for RenderRunner {
                         let scope = state.inner.any_scope();
                         let next_sibling = state.next_sibling.clone();
 
+                        #[cfg(feature = "render_hooks")]
+                        crate::app_handle::render_start(&scope);
                         let node = new_root.apply(&scope, m, next_sibling, ancestor);
                         state.node_ref.link(node);
 
@@ -384,6 +413,19 @@ impl Runnable for RenderedRunner {
             crate::virtual_dom::vcomp::log_event(state.vcomp_id, "rendered");
 
             if state.suspension.is_none() && state.parent.is_some() {
+                let content_node_count = state.root_node.content_node_count();
+                let mut rendered_root = state.rendered_root.borrow_mut();
+                let last_content_node_count = rendered_root
+                    .as_ref()
+                    .map_or(0, |root| root.content_node_count);
+                *rendered_root = Some(RenderedRoot {
+                    first_node: state.root_node.first_node(),
+                    node_count: state.root_node.node_count(),
+                    content_node_count,
+                    node_count_delta: content_node_count as isize
+                        - last_content_node_count as isize,
+                });
+                drop(rendered_root);
                 state.inner.rendered(self.first_render);
             }
         }
@@ -396,10 +438,11 @@ mod tests {
 
     use crate::html;
     use crate::html::*;
-    use crate::Properties;
+    use crate::{Callback, Properties};
     use std::ops::Deref;
     #[cfg(feature = "wasm_test")]
     use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+    use web_sys::Node;
 
     #[cfg(feature = "wasm_test")]
     wasm_bindgen_test_configure!(run_in_browser);
@@ -518,7 +561,12 @@ mod tests {
         let lifecycle = props.lifecycle.clone();
 
         lifecycle.borrow_mut().clear();
-        scope.mount_in_place(el, NodeRef::default(), NodeRef::default(), Rc::new(props));
+        scope.mount_in_place(
+            el.into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::new(props),
+        );
         crate::scheduler::start_now();
 
         assert_eq!(&lifecycle.borrow_mut().deref()[..], expected);
@@ -637,4 +685,297 @@ mod tests {
             ],
         );
     }
+
+    #[cfg(feature = "wasm_test")]
+    #[test]
+    async fn send_message_batch_renders_once() {
+        let lifecycle: Rc<RefCell<Vec<String>>> = Rc::default();
+        let document = gloo_utils::document();
+        let scope = Scope::<Comp>::new(None);
+        let el = document.create_element("div").unwrap();
+        let props = Props {
+            lifecycle: lifecycle.clone(),
+            ..Props::default()
+        };
+        scope.mount_in_place(
+            el.into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::new(props),
+        );
+        crate::scheduler::start_now();
+        lifecycle.borrow_mut().clear();
+
+        let link = scope.clone();
+        async move {
+            link.send_message_batch(vec![false, true, true]);
+        }
+        .await;
+        crate::scheduler::start_now();
+
+        assert_eq!(
+            &lifecycle.borrow().deref()[..],
+            &[
+                "update(false)",
+                "update(true)",
+                "update(true)",
+                "view",
+                "rendered(false)",
+            ]
+        );
+    }
+
+    #[test]
+    fn destroy_runs_once_before_detach_and_children() {
+        #[derive(Clone, Properties, PartialEq)]
+        struct TrackedProps {
+            name: &'static str,
+            lifecycle: Rc<RefCell<Vec<String>>>,
+            #[prop_or_default]
+            children: Children,
+        }
+
+        struct Tracked {
+            node_ref: NodeRef,
+        }
+
+        impl Component for Tracked {
+            type Message = ();
+            type Properties = TrackedProps;
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                Tracked {
+                    node_ref: NodeRef::default(),
+                }
+            }
+
+            fn view(&self, ctx: &Context<Self>) -> Html {
+                html! { <div ref={self.node_ref.clone()}>{ ctx.props().children.clone() }</div> }
+            }
+
+            fn destroy(&mut self, ctx: &Context<Self>) {
+                let attached = self
+                    .node_ref
+                    .get()
+                    .and_then(|node| node.parent_node())
+                    .is_some();
+                ctx.props().lifecycle.borrow_mut().push(format!(
+                    "destroy {} (attached: {})",
+                    ctx.props().name,
+                    attached
+                ));
+            }
+        }
+
+        let lifecycle: Rc<RefCell<Vec<String>>> = Rc::default();
+        let document = gloo_utils::document();
+        let scope = Scope::<Tracked>::new(None);
+        let el = document.create_element("div").unwrap();
+        scope.mount_in_place(
+            el.clone().into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::new(TrackedProps {
+                name: "parent",
+                lifecycle: lifecycle.clone(),
+                children: Children::new(vec![
+                    html! { <Tracked name="child" lifecycle={lifecycle.clone()} /> },
+                ]),
+            }),
+        );
+        crate::scheduler::start_now();
+        assert_eq!(el.inner_html(), "<div><div></div></div>");
+
+        let expected = vec![
+            "destroy parent (attached: true)".to_string(),
+            "destroy child (attached: true)".to_string(),
+        ];
+        scope.clone().destroy(false);
+        crate::scheduler::start_now();
+        assert_eq!(*lifecycle.borrow(), expected);
+        assert_eq!(el.inner_html(), "");
+
+        // Destroying again has no effect
+        scope.clone().destroy(false);
+        crate::scheduler::start_now();
+        assert_eq!(*lifecycle.borrow(), expected);
+    }
+
+    #[test]
+    fn root_node_resolves_after_rendered() {
+        struct Comp {
+            root: Rc<RefCell<Option<Node>>>,
+        }
+
+        impl Component for Comp {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                Comp {
+                    root: Rc::default(),
+                }
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! { <><span>{ "first" }</span><span>{ "second" }</span></> }
+            }
+
+            fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+                *self.root.borrow_mut() = ctx.link().root_node();
+            }
+        }
+
+        let document = gloo_utils::document();
+        let scope = Scope::<Comp>::new(None);
+        let el = document.create_element("div").unwrap();
+        assert!(scope.root_node().is_none());
+
+        scope.mount_in_place(
+            el.clone().into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::new(()),
+        );
+        crate::scheduler::start_now();
+
+        let first = el.first_child();
+        assert!(first.is_some());
+        assert_eq!(scope.root_node(), first);
+        assert_eq!(*scope.get_component().unwrap().root.borrow(), first);
+    }
+
+    #[test]
+    fn failed_create_renders_fallback() {
+        struct Fallible;
+
+        impl Component for Fallible {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                unreachable!("fallible components are created through `try_create`")
+            }
+
+            fn try_create(_ctx: &Context<Self>) -> Result<Self, Html> {
+                Err(html! { <p>{ "fallback" }</p> })
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! { <p>{ "component" }</p> }
+            }
+        }
+
+        let document = gloo_utils::document();
+        let scope = Scope::<Fallible>::new(None);
+        let el = document.create_element("div").unwrap();
+        scope.mount_in_place(
+            el.clone().into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::new(()),
+        );
+        crate::scheduler::start_now();
+
+        assert_eq!(el.inner_html(), "<p>fallback</p>");
+        assert!(scope.get_component().is_none());
+
+        // Messages to a component that failed to be created are dropped
+        scope.send_message(());
+        crate::scheduler::start_now();
+        assert_eq!(el.inner_html(), "<p>fallback</p>");
+    }
+
+    #[test]
+    fn shared_props_are_not_compared() {
+        #[derive(PartialEq, Properties)]
+        struct CountedProps {
+            value: u32,
+        }
+
+        struct Counted;
+
+        impl Component for Counted {
+            type Message = ();
+            type Properties = CountedProps;
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                Counted
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! {}
+            }
+        }
+
+        let document = gloo_utils::document();
+        let scope = Scope::<Counted>::new(None);
+        let el = document.create_element("div").unwrap();
+        let props = Rc::new(CountedProps { value: 1 });
+        scope.mount_in_place(
+            el.into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::clone(&props),
+        );
+        crate::scheduler::start_now();
+        let comparisons = super::PROPS_COMPARISONS.with(|c| c.get());
+
+        scope.reuse(props, NodeRef::default(), NodeRef::default());
+        crate::scheduler::start_now();
+        assert_eq!(super::PROPS_COMPARISONS.with(|c| c.get()), comparisons);
+
+        // Equal props in a different allocation
+        scope.reuse(
+            Rc::new(CountedProps { value: 1 }),
+            NodeRef::default(),
+            NodeRef::default(),
+        );
+        crate::scheduler::start_now();
+        assert_eq!(super::PROPS_COMPARISONS.with(|c| c.get()), comparisons + 1);
+    }
+
+    #[test]
+    fn props_rc_shares_storage() {
+        #[derive(PartialEq, Properties)]
+        struct CaptureProps {
+            value: u32,
+        }
+
+        struct Capture {
+            get_value: Callback<(), u32>,
+        }
+
+        impl Component for Capture {
+            type Message = ();
+            type Properties = CaptureProps;
+
+            fn create(ctx: &Context<Self>) -> Self {
+                let props = ctx.props_rc();
+                Self {
+                    get_value: Callback::from(move |_| props.value),
+                }
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! {}
+            }
+        }
+
+        let document = gloo_utils::document();
+        let scope = Scope::<Capture>::new(None);
+        let el = document.create_element("div").unwrap();
+        let props = Rc::new(CaptureProps { value: 42 });
+        scope.mount_in_place(
+            el.into(),
+            NodeRef::default(),
+            NodeRef::default(),
+            Rc::clone(&props),
+        );
+        crate::scheduler::start_now();
+
+        assert_eq!(scope.get_component().unwrap().get_value.emit(()), 42);
+        // Shared by this test, the component's context and the callback
+        assert_eq!(Rc::strong_count(&props), 3);
+    }
 }
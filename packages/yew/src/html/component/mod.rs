@@ -1,15 +1,18 @@
 //! Components wrapped with context including properties, state, and link
 
 mod children;
+mod component_ref;
 mod lifecycle;
 mod properties;
 mod scope;
 
-use super::{Html, HtmlResult, IntoHtmlResult};
+use super::{Html, HtmlResult};
+use crate::virtual_dom::Key;
 pub use children::*;
+pub use component_ref::*;
 pub use properties::*;
-pub(crate) use scope::Scoped;
 pub use scope::{AnyScope, Scope, SendAsMessage};
+pub(crate) use scope::{RenderedRoot, Scoped};
 use std::rc::Rc;
 
 /// The [`Component`]'s context. This contains component's [`Scope`] and and props and
@@ -32,6 +35,15 @@ impl<COMP: BaseComponent> Context<COMP> {
     pub fn props(&self) -> &COMP::Properties {
         &*self.props
     }
+
+    /// The component's props as a shared reference.
+    ///
+    /// Cloning the returned [`Rc`] is cheap, which makes it suitable for capturing the props in
+    /// callbacks.
+    #[inline]
+    pub fn props_rc(&self) -> Rc<COMP::Properties> {
+        Rc::clone(&self.props)
+    }
 }
 
 /// A Sealed trait that prevents direct implementation of
@@ -51,9 +63,20 @@ pub trait BaseComponent: SealedBaseComponent + Sized + 'static {
     /// The Component's properties.
     type Properties: Properties;
 
+    /// Returns the key of the component, which is derived from its properties.
+    #[allow(unused_variables)]
+    fn key(props: &Self::Properties) -> Option<Key> {
+        None
+    }
+
     /// Creates a component.
     fn create(ctx: &Context<Self>) -> Self;
 
+    /// Creates a component, or returns the [`Html`] to render in its place.
+    fn try_create(ctx: &Context<Self>) -> Result<Self, Html> {
+        Ok(Self::create(ctx))
+    }
+
     /// Updates component's internal state.
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool;
 
@@ -87,9 +110,31 @@ pub trait Component: Sized + 'static {
     /// receive new properties in the context passed to the `changed` lifecycle method.
     type Properties: Properties;
 
+    /// Returns the key of the component, derived from its properties.
+    ///
+    /// Components, which have a natural identity in their properties (e.g. an id), can use this
+    /// to be keyed in lists without passing `key` to every instance. A `key` passed in `html!`
+    /// takes precedence.
+    #[allow(unused_variables)]
+    fn key(props: &Self::Properties) -> Option<Key> {
+        None
+    }
+
     /// Called when component is created.
     fn create(ctx: &Context<Self>) -> Self;
 
+    /// Called instead of `create` when the component is created, allowing creation to fail.
+    ///
+    /// Returning `Err` renders the given fallback [`Html`] in place of the component. No other
+    /// lifecycle methods are called on a component that failed to be created and messages sent
+    /// to it are dropped.
+    ///
+    /// The default implementation calls `create`. Components overriding this method still
+    /// implement `create`, which the override can call once creation succeeds.
+    fn try_create(ctx: &Context<Self>) -> Result<Self, Html> {
+        Ok(Self::create(ctx))
+    }
+
     /// Called when a new message is sent to the component via its scope.
     ///
     /// Components handle messages in their `update` method and commonly use this method
@@ -118,6 +163,19 @@ pub trait Component: Sized + 'static {
     /// The `create()` call is always followed by a call to `view()`.
     fn view(&self, ctx: &Context<Self>) -> Html;
 
+    /// Called instead of `view` to render the component, allowing rendering to suspend.
+    ///
+    /// Returning [`RenderError::Suspended`](crate::html::RenderError::Suspended) shows the
+    /// fallback of the closest `<Suspense />` until the [`Suspension`](crate::suspense::Suspension)
+    /// is resumed, after which this method is called again. This allows expensive views to be
+    /// computed asynchronously without blocking.
+    ///
+    /// The default implementation calls `view`. Components overriding this method still
+    /// implement `view`, which the override can call once it doesn't suspend.
+    fn try_view(&self, ctx: &Context<Self>) -> HtmlResult {
+        Ok(self.view(ctx))
+    }
+
     /// The `rendered` method is called after each time a Component is rendered but
     /// before the browser updates the page.
     ///
@@ -128,6 +186,10 @@ pub trait Component: Sized + 'static {
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {}
 
     /// Called right before a Component is unmounted.
+    ///
+    /// This is called exactly once and the rendered DOM of the component is still in place.
+    /// Components are notified before their children, which are destroyed after the DOM of
+    /// their parent has been removed from the document.
     #[allow(unused_variables)]
     fn destroy(&mut self, ctx: &Context<Self>) {}
 }
@@ -140,10 +202,18 @@ where
 
     type Properties = <T as Component>::Properties;
 
+    fn key(props: &Self::Properties) -> Option<Key> {
+        Component::key(props)
+    }
+
     fn create(ctx: &Context<Self>) -> Self {
         Component::create(ctx)
     }
 
+    fn try_create(ctx: &Context<Self>) -> Result<Self, Html> {
+        Component::try_create(ctx)
+    }
+
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         Component::update(self, ctx, msg)
     }
@@ -153,7 +223,7 @@ where
     }
 
     fn view(&self, ctx: &Context<Self>) -> HtmlResult {
-        Component::view(self, ctx).into_html_result()
+        Component::try_view(self, ctx)
     }
 
     fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
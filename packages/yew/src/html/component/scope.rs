@@ -19,7 +19,21 @@ use std::marker::PhantomData;
 use std::ops::Deref;
 use std::rc::Rc;
 use std::{fmt, iter};
-use web_sys::{Element, Node};
+use web_sys::Node;
+
+/// The rendered root of a component as of its last call to `rendered`.
+///
+/// The state of a component is borrowed while its lifecycle methods run, so this is used to
+/// answer queries about the rendered nodes from within them.
+#[derive(Debug, Default)]
+pub(crate) struct RenderedRoot {
+    pub(crate) first_node: Option<Node>,
+    pub(crate) node_count: usize,
+    /// See [VNode::content_node_count]
+    pub(crate) content_node_count: usize,
+    /// The change of `content_node_count` caused by the last render
+    pub(crate) node_count_delta: isize,
+}
 
 #[derive(Debug)]
 pub(crate) struct MsgQueue<Msg>(Shared<Vec<Msg>>);
@@ -65,6 +79,11 @@ pub struct AnyScope {
     type_id: TypeId,
     parent: Option<Rc<AnyScope>>,
     state: Shared<Option<ComponentState>>,
+    pub(crate) rendered_root: Shared<Option<RenderedRoot>>,
+
+    /// Identifies the app this scope belongs to by the state of its root component
+    #[cfg(feature = "render_hooks")]
+    pub(crate) app_id: usize,
 
     #[cfg(debug_assertions)]
     pub(crate) vcomp_id: usize,
@@ -82,6 +101,10 @@ impl<COMP: BaseComponent> From<Scope<COMP>> for AnyScope {
             type_id: TypeId::of::<COMP>(),
             parent: scope.parent,
             state: scope.state,
+            rendered_root: scope.rendered_root,
+
+            #[cfg(feature = "render_hooks")]
+            app_id: scope.app_id,
 
             #[cfg(debug_assertions)]
             vcomp_id: scope.vcomp_id,
@@ -96,6 +119,10 @@ impl AnyScope {
             type_id: TypeId::of::<()>(),
             parent: None,
             state: Rc::new(RefCell::new(None)),
+            rendered_root: Rc::default(),
+
+            #[cfg(feature = "render_hooks")]
+            app_id: 0,
 
             #[cfg(debug_assertions)]
             vcomp_id: 0,
@@ -156,7 +183,8 @@ pub(crate) trait Scoped {
     fn to_any(&self) -> AnyScope;
     fn root_vnode(&self) -> Option<Ref<'_, VNode>>;
     fn destroy(&mut self, parent_to_detach: bool);
-    fn shift_node(&self, parent: Element, next_sibling: NodeRef);
+    fn shift_node(&self, parent: Node, next_sibling: NodeRef);
+    fn type_name(&self) -> &'static str;
 }
 
 impl<COMP: BaseComponent> Scoped for Scope<COMP> {
@@ -177,6 +205,9 @@ impl<COMP: BaseComponent> Scoped for Scope<COMP> {
 
     /// Process an event to destroy a component
     fn destroy(&mut self, parent_to_detach: bool) {
+        #[cfg(any(target_arch = "wasm32", feature = "tokio"))]
+        self.abort_tasks();
+
         scheduler::push_component_destroy(DestroyRunner {
             state: self.state.clone(),
             parent_to_detach,
@@ -185,12 +216,16 @@ impl<COMP: BaseComponent> Scoped for Scope<COMP> {
         scheduler::start();
     }
 
-    fn shift_node(&self, parent: Element, next_sibling: NodeRef) {
+    fn shift_node(&self, parent: Node, next_sibling: NodeRef) {
         scheduler::push_component_update(UpdateRunner {
             state: self.state.clone(),
             event: UpdateEvent::Shift(parent, next_sibling),
         });
     }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<COMP>()
+    }
 }
 
 /// A context which allows sending messages to a component.
@@ -199,6 +234,14 @@ pub struct Scope<COMP: BaseComponent> {
     parent: Option<Rc<AnyScope>>,
     pub(crate) pending_messages: MsgQueue<COMP::Message>,
     pub(crate) state: Shared<Option<ComponentState>>,
+    pub(crate) rendered_root: Shared<Option<RenderedRoot>>,
+
+    #[cfg(any(target_arch = "wasm32", feature = "tokio"))]
+    tasks: Shared<feat_io::ScopedTasks>,
+
+    /// Identifies the app this scope belongs to by the state of its root component
+    #[cfg(feature = "render_hooks")]
+    pub(crate) app_id: usize,
 
     #[cfg(debug_assertions)]
     pub(crate) vcomp_id: usize,
@@ -217,6 +260,13 @@ impl<COMP: BaseComponent> Clone for Scope<COMP> {
             pending_messages: self.pending_messages.clone(),
             parent: self.parent.clone(),
             state: self.state.clone(),
+            rendered_root: self.rendered_root.clone(),
+
+            #[cfg(any(target_arch = "wasm32", feature = "tokio"))]
+            tasks: self.tasks.clone(),
+
+            #[cfg(feature = "render_hooks")]
+            app_id: self.app_id,
 
             #[cfg(debug_assertions)]
             vcomp_id: self.vcomp_id,
@@ -233,9 +283,17 @@ impl<COMP: BaseComponent> Scope<COMP> {
     /// Returns the linked component if available
     pub fn get_component(&self) -> Option<impl Deref<Target = COMP> + '_> {
         self.state.try_borrow().ok().and_then(|state_ref| {
-            state_ref.as_ref()?;
+            let inner = state_ref
+                .as_ref()?
+                .inner
+                .as_any()
+                .downcast_ref::<CompStateInner<COMP>>()
+                .unwrap();
+            // Components which failed to be created only have a fallback
+            inner.component.as_ref().ok()?;
+
             Some(Ref::map(state_ref, |state| {
-                &state
+                match &state
                     .as_ref()
                     .unwrap()
                     .inner
@@ -243,15 +301,51 @@ impl<COMP: BaseComponent> Scope<COMP> {
                     .downcast_ref::<CompStateInner<COMP>>()
                     .unwrap()
                     .component
+                {
+                    Ok(component) => component,
+                    Err(_) => unreachable!(),
+                }
             }))
         })
     }
 
+    /// Returns the first DOM node rendered by the component, if it has been mounted.
+    ///
+    /// This is available from [`Component::rendered`](crate::html::Component::rendered) onwards
+    /// and can be used for imperative operations without passing a [`NodeRef`] to the root.
+    pub fn root_node(&self) -> Option<Node> {
+        match self.state.try_borrow() {
+            Ok(_) => self.root_vnode()?.first_node(),
+            // Called from a lifecycle method of the component itself
+            Err(_) => self.rendered_root.borrow().as_ref()?.first_node.clone(),
+        }
+    }
+
+    /// Returns the number of top-level DOM nodes rendered by the component, if it has been
+    /// mounted.
+    ///
+    /// Nodes of nested components are counted towards their parent, while portals do not
+    /// contribute any nodes. An empty fragment renders a single placeholder node. Like
+    /// [`root_node`](Self::root_node), this is available from
+    /// [`Component::rendered`](crate::html::Component::rendered) onwards.
+    pub fn node_count(&self) -> Option<usize> {
+        match self.state.try_borrow() {
+            Ok(_) => Some(self.root_vnode()?.node_count()),
+            // Called from a lifecycle method of the component itself
+            Err(_) => Some(self.rendered_root.borrow().as_ref()?.node_count),
+        }
+    }
+
     pub(crate) fn new(parent: Option<AnyScope>) -> Self {
         let parent = parent.map(Rc::new);
         let state = Rc::new(RefCell::new(None));
         let pending_messages = MsgQueue::new();
 
+        #[cfg(feature = "render_hooks")]
+        let app_id = parent
+            .as_ref()
+            .map_or(state.as_ptr() as usize, |p| p.app_id);
+
         #[cfg(debug_assertions)]
         let vcomp_id = parent.as_ref().map(|p| p.vcomp_id).unwrap_or_default();
 
@@ -259,8 +353,15 @@ impl<COMP: BaseComponent> Scope<COMP> {
             _marker: PhantomData,
             pending_messages,
             state,
+            rendered_root: Rc::default(),
             parent,
 
+            #[cfg(any(target_arch = "wasm32", feature = "tokio"))]
+            tasks: Rc::default(),
+
+            #[cfg(feature = "render_hooks")]
+            app_id,
+
             #[cfg(debug_assertions)]
             vcomp_id,
         }
@@ -269,7 +370,7 @@ impl<COMP: BaseComponent> Scope<COMP> {
     /// Mounts a component with `props` to the specified `element` in the DOM.
     pub(crate) fn mount_in_place(
         &self,
-        parent: Element,
+        parent: Node,
         next_sibling: NodeRef,
         node_ref: NodeRef,
         props: Rc<COMP::Properties>,
@@ -323,6 +424,14 @@ impl<COMP: BaseComponent> Scope<COMP> {
         scheduler::start();
     }
 
+    /// Returns `true`, if the component was created and not destroyed yet.
+    pub(crate) fn is_mounted(&self) -> bool {
+        // The state is borrowed while the component is rendered or updated
+        self.state
+            .try_borrow()
+            .map_or(true, |state| state.is_some())
+    }
+
     /// Send a message to the component.
     pub fn send_message<T>(&self, msg: T)
     where
@@ -335,6 +444,10 @@ impl<COMP: BaseComponent> Scope<COMP> {
     }
 
     /// Send a batch of messages to the component.
+    ///
+    /// All messages are processed in a single update cycle, so the component is re-rendered at
+    /// most once for the whole batch. This is useful when messages are produced outside of a
+    /// callback, for example by an async task.
     pub fn send_message_batch(&self, mut messages: Vec<COMP::Message>) {
         let msg_len = messages.len();
 
@@ -396,10 +509,17 @@ impl<COMP: BaseComponent> Scope<COMP> {
 #[cfg(feature = "ssr")]
 mod feat_ssr {
     use super::*;
+    use crate::html::RenderResult;
+    use crate::virtual_dom::RenderOptions;
     use futures::channel::oneshot;
 
     impl<COMP: BaseComponent> Scope<COMP> {
-        pub(crate) async fn render_to_string(&self, w: &mut String, props: Rc<COMP::Properties>) {
+        pub(crate) async fn render_to_string(
+            &self,
+            w: &mut String,
+            props: Rc<COMP::Properties>,
+            options: RenderOptions,
+        ) -> RenderResult<()> {
             let (tx, rx) = oneshot::channel();
 
             scheduler::push_component_create(
@@ -421,25 +541,126 @@ mod feat_ssr {
             let html = rx.await.unwrap();
 
             let self_any_scope = self.to_any();
-            html.render_to_string(w, &self_any_scope).await;
+            let result = html.render_to_string(w, &self_any_scope, options).await;
 
+            #[cfg(feature = "tokio")]
+            self.abort_tasks();
             scheduler::push_component_destroy(DestroyRunner {
                 state: self.state.clone(),
                 parent_to_detach: false,
             });
             scheduler::start();
+
+            result
         }
     }
 }
 #[cfg_attr(documenting, doc(cfg(any(target_arch = "wasm32", feature = "tokio"))))]
 #[cfg(any(target_arch = "wasm32", feature = "tokio"))]
 mod feat_io {
+    use std::cell::Cell;
     use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Weak;
+    use std::task::{Context, Poll};
 
     use super::*;
     use crate::io_coop::spawn_local;
 
+    type BoxedFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+    /// Tasks spawned by a component, which are aborted once it is destroyed
+    #[derive(Default)]
+    pub(crate) struct ScopedTasks {
+        aborted: bool,
+        tasks: Vec<Weak<ScopedTask>>,
+    }
+
+    struct ScopedTask {
+        aborted: Cell<bool>,
+        future: RefCell<Option<BoxedFuture>>,
+    }
+
+    impl ScopedTask {
+        fn abort(&self) {
+            self.aborted.set(true);
+            // A task which is being polled drops its future itself, once the poll returns
+            let future = match self.future.try_borrow_mut() {
+                Ok(mut future) => future.take(),
+                Err(_) => None,
+            };
+            drop(future);
+        }
+    }
+
+    /// A future which is dropped as soon as its component is destroyed
+    struct ScopedFuture {
+        task: Rc<ScopedTask>,
+    }
+
+    impl Future for ScopedFuture {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let mut slot = self.task.future.borrow_mut();
+            let poll = match slot.as_mut() {
+                Some(future) if !self.task.aborted.get() => future.as_mut().poll(cx),
+                _ => Poll::Ready(()),
+            };
+
+            // The component might have been destroyed while polling
+            if poll.is_ready() || self.task.aborted.get() {
+                let future = slot.take();
+                // Release the borrow first, in case dropping the future spawns another task
+                drop(slot);
+                drop(future);
+                return Poll::Ready(());
+            }
+            poll
+        }
+    }
+
     impl<COMP: BaseComponent> Scope<COMP> {
+        /// Drops all tasks spawned with [`Scope::spawn_local`] and prevents spawning new ones.
+        pub(crate) fn abort_tasks(&self) {
+            let tasks = {
+                let mut tasks = self.tasks.borrow_mut();
+                tasks.aborted = true;
+                std::mem::take(&mut tasks.tasks)
+            };
+
+            for task in tasks.iter().filter_map(Weak::upgrade) {
+                task.abort();
+            }
+        }
+
+        /// Spawns a task, which is aborted once the component is destroyed.
+        ///
+        /// Use this for work such as fetches, whose continuation should not run after the
+        /// component was unmounted. The task is dropped as soon as the component is destroyed,
+        /// even if it was not polled yet. Tasks spawned after that are not run at all.
+        pub fn spawn_local<F>(&self, future: F)
+        where
+            F: Future<Output = ()> + 'static,
+        {
+            let task = {
+                let mut tasks = self.tasks.borrow_mut();
+                if tasks.aborted {
+                    return;
+                }
+
+                let task = Rc::new(ScopedTask {
+                    aborted: Cell::new(false),
+                    future: RefCell::new(Some(Box::pin(future))),
+                });
+                tasks.tasks.retain(|task| task.strong_count() > 0);
+                tasks.tasks.push(Rc::downgrade(&task));
+                task
+            };
+
+            spawn_local(ScopedFuture { task });
+        }
+
         /// This method creates a [`Callback`] which returns a Future which
         /// returns a message to be sent back to the component's event
         /// loop.
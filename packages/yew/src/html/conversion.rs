@@ -1,4 +1,4 @@
-use super::{Component, NodeRef, Scope};
+use super::{AutoCapitalize, Component, EnterKeyHint, InputMode, NodeRef, Scope};
 use crate::virtual_dom::AttrValue;
 use std::{borrow::Cow, rc::Rc};
 
@@ -88,6 +88,10 @@ impl_into_prop!(|value: &'static str| -> AttrValue { AttrValue::Static(value) })
 impl_into_prop!(|value: String| -> AttrValue { AttrValue::Owned(value) });
 impl_into_prop!(|value: Rc<str>| -> AttrValue { AttrValue::Rc(value) });
 
+impl_into_prop!(|value: InputMode| -> AttrValue { AttrValue::Static(value.as_str()) });
+impl_into_prop!(|value: AutoCapitalize| -> AttrValue { AttrValue::Static(value.as_str()) });
+impl_into_prop!(|value: EnterKeyHint| -> AttrValue { AttrValue::Static(value.as_str()) });
+
 #[cfg(test)]
 mod test {
     use super::*;
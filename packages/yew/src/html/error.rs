@@ -1,13 +1,24 @@
 use thiserror::Error;
 
 use crate::suspense::Suspension;
+use crate::virtual_dom::Key;
 
 /// Render Error.
+///
+/// More kinds of errors may be added in the future, so matches on it need a wildcard arm.
 #[derive(Error, Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum RenderError {
     /// Component Rendering Suspended
     #[error("component rendering is suspended.")]
     Suspended(#[from] Suspension),
+
+    /// A keyed list contains the same key more than once.
+    ///
+    /// This is only reported by `ServerRenderer::try_render`, as duplicate keys would otherwise
+    /// silently produce markup that no longer matches the virtual DOM.
+    #[error("duplicate key `{0}` found in a keyed list.")]
+    DuplicateKey(Key),
 }
 
 /// Render Result.
@@ -2,7 +2,7 @@
 mod events;
 
 use wasm_bindgen::JsCast;
-use web_sys::{Event, EventTarget};
+use web_sys::{Event, EventTarget, HtmlElement, InputEvent};
 
 use crate::Callback;
 pub use events::*;
@@ -131,6 +131,40 @@ where
 
 impl<E: AsRef<Event>> TargetCast for E {}
 
+/// Creates an `oninput` listener for a `contenteditable` element, which emits the element's
+/// text content.
+///
+/// Unlike form fields, `contenteditable` elements have no `value`, so the edited text has to be
+/// read from the element itself.
+///
+/// # Example
+///
+/// ```
+/// use yew::prelude::*;
+/// use yew::html::contenteditable_oninput;
+/// # enum Msg {
+/// #   Edit(String),
+/// # }
+/// # struct Comp;
+/// # impl Component for Comp {
+/// # type Message = Msg;
+/// # type Properties = ();
+/// # fn create(ctx: &Context<Self>) -> Self {
+/// #   Self
+/// # }
+///
+/// fn view(&self, ctx: &Context<Self>) -> Html {
+///     html! {
+///         <div contenteditable="true" oninput={contenteditable_oninput(ctx.link().callback(Msg::Edit))} />
+///     }
+/// }
+/// # }
+/// ```
+pub fn contenteditable_oninput(callback: Callback<String>) -> Callback<InputEvent> {
+    callback
+        .map_filter(|e: InputEvent| e.target_dyn_into::<HtmlElement>().map(|el| el.inner_text()))
+}
+
 /// A trait similar to `Into<T>` which allows conversion of a value into a [`Callback`].
 /// This is used for event listeners.
 pub trait IntoEventCallback<EVENT> {
@@ -1,11 +1,13 @@
 //! The main html module which defines components, listeners, and class helpers.
 
+mod attributes;
 mod classes;
 mod component;
 mod conversion;
 mod error;
 mod listener;
 
+pub use attributes::*;
 pub use classes::*;
 pub use component::*;
 pub use conversion::*;
@@ -13,11 +15,11 @@ pub use error::*;
 pub use listener::*;
 
 use crate::sealed::Sealed;
-use crate::virtual_dom::{VNode, VPortal};
+use crate::virtual_dom::{Key, VList, VNode, VNothing, VPortal};
 use std::cell::RefCell;
 use std::rc::Rc;
-use wasm_bindgen::JsValue;
-use web_sys::{Element, Node};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, HtmlInputElement, HtmlTextAreaElement, Node, ShadowRoot};
 
 /// A type which expected as a result of `view` function implementation.
 pub type Html = VNode;
@@ -124,6 +126,36 @@ impl NodeRef {
         node.map(Into::into).map(INTO::from)
     }
 
+    /// Try converting the node reference into another form, panicking if the reference is empty.
+    ///
+    /// This is mostly useful in tests, where the node is known to be rendered.
+    pub fn cast_or_panic<INTO: AsRef<Node> + From<JsValue>>(&self) -> INTO {
+        self.cast()
+            .expect("tried to cast a `NodeRef` which doesn't reference a node")
+    }
+
+    /// Get the referenced node as an [`Element`].
+    ///
+    /// Unlike [`cast`](Self::cast), this checks the type of the node and returns `None` if the
+    /// reference points to a non-element node such as a text node.
+    pub fn into_element(&self) -> Option<Element> {
+        self.get()?.dyn_into().ok()
+    }
+
+    /// Get the referenced node as an [`HtmlInputElement`].
+    ///
+    /// Returns `None` if the reference is empty or doesn't point to an `<input>` element.
+    pub fn into_input(&self) -> Option<HtmlInputElement> {
+        self.get()?.dyn_into().ok()
+    }
+
+    /// Get the referenced node as an [`HtmlTextAreaElement`].
+    ///
+    /// Returns `None` if the reference is empty or doesn't point to a `<textarea>` element.
+    pub fn into_textarea(&self) -> Option<HtmlTextAreaElement> {
+        self.get()?.dyn_into().ok()
+    }
+
     /// Wrap an existing `Node` in a `NodeRef`
     pub(crate) fn new(node: Node) -> Self {
         let node_ref = NodeRef::default();
@@ -172,6 +204,53 @@ pub fn create_portal(child: Html, host: Element) -> Html {
     VNode::VPortal(VPortal::new(child, host))
 }
 
+/// Render nothing at all.
+///
+/// Unlike an empty fragment (`html! {}`, which is what [`Html::default()`](VNode::default)
+/// returns), this does not insert a placeholder text node into the DOM.
+pub fn nothing() -> Html {
+    VNode::VNothing(VNothing::new())
+}
+
+/// Render `html`, recreating it from scratch whenever the enum variant of `value` changes.
+///
+/// Subtrees rendered for different variants are never patched into each other, which avoids
+/// mismatched component state when e.g. switching between completely different routes. Renders
+/// for the same variant are diffed as usual.
+///
+/// # Example
+/// ```
+/// # use yew::prelude::*;
+/// # use yew::html::keyed_by_discriminant;
+/// enum Route {
+///     Home,
+///     Post(u32),
+/// }
+///
+/// fn render(route: &Route) -> Html {
+///     keyed_by_discriminant(
+///         route,
+///         match route {
+///             Route::Home => html! { <h1>{ "Home" }</h1> },
+///             Route::Post(id) => html! { <h1>{ format!("Post {}", id) }</h1> },
+///         },
+///     )
+/// }
+/// ```
+pub fn keyed_by_discriminant<T: 'static>(value: &T, html: Html) -> Html {
+    let key = Key::from_hashable(&std::mem::discriminant(value));
+    let keyed = VNode::VList(VList::with_children(vec![html], Some(key)));
+
+    // The list is only compared by key when diffed as a child of a fully keyed list
+    VNode::VList(VList::with_children(vec![keyed], None))
+}
+
+/// Render children into the shadow tree of a [`ShadowRoot`], e.g. to encapsulate the styles of
+/// a web component.
+pub fn create_shadow_portal(child: Html, shadow_root: ShadowRoot) -> Html {
+    VNode::VPortal(VPortal::new_in_shadow_root(child, shadow_root))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,4 +277,61 @@ mod tests {
         node_ref_2.link(node_ref);
         assert_eq!(node, node_ref_2.get().unwrap());
     }
+
+    #[test]
+    fn keyed_by_discriminant_recreates_on_variant_change() {
+        use crate::virtual_dom::VDiff;
+
+        enum Route {
+            A(u32),
+            B,
+        }
+
+        let render = |route: &Route| {
+            let id = match route {
+                Route::A(id) => id.to_string(),
+                Route::B => "b".to_string(),
+            };
+            keyed_by_discriminant(route, crate::html! { <p id={id}></p> })
+        };
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let mut node = render(&Route::A(1));
+        node.apply(&scope, &parent, NodeRef::default(), None);
+        let first = parent.first_element_child().unwrap();
+
+        // Same variant: the element is patched
+        let mut next = render(&Route::A(2));
+        next.apply(&scope, &parent, NodeRef::default(), Some(node));
+        assert_eq!(parent.first_element_child().unwrap(), first);
+        assert_eq!(first.id(), "2");
+
+        // Different variant: the subtree is recreated
+        let mut last = render(&Route::B);
+        last.apply(&scope, &parent, NodeRef::default(), Some(next));
+        assert_ne!(parent.first_element_child().unwrap(), first);
+        assert_eq!(parent.inner_html(), "<p id=\"b\"></p>");
+    }
+
+    #[test]
+    fn typed_node_ref_conversions() {
+        let input = document().create_element("input").unwrap();
+        input.set_attribute("value", "hello").unwrap();
+        let node_ref = NodeRef::new(input.into());
+
+        assert!(node_ref.into_element().is_some());
+        assert!(node_ref.into_textarea().is_none());
+        assert_eq!(node_ref.into_input().unwrap().value(), "hello");
+        assert_eq!(
+            node_ref.cast_or_panic::<HtmlInputElement>().value(),
+            "hello"
+        );
+
+        let text = NodeRef::new(document().create_text_node("text").into());
+        assert!(text.into_element().is_none());
+        assert!(NodeRef::default().into_input().is_none());
+    }
 }
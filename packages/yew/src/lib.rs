@@ -98,9 +98,10 @@ use std::{cell::Cell, panic::PanicInfo};
 /// # fn test() {
 /// let conditional_class = Some("my-other-class");
 /// let vec_of_classes = vec!["one-bean", "two-beans", "three-beans", "a-very-small-casserole"];
+/// let active = true;
 ///
 /// html! {
-///     <div class={classes!("my-container-class", conditional_class, vec_of_classes)}>
+///     <div class={classes!("my-container-class", conditional_class, vec_of_classes, ("active", active))}>
 ///         // ...
 ///     </div>
 /// };
@@ -113,10 +114,38 @@ pub use yew_macro::classes;
 /// This macro always returns [`Html`].
 /// If you need to preserve the type of a component, use the [`html_nested!`] macro instead.
 ///
+/// # Whitespace
+///
+/// Whitespace in the macro input itself is not significant: every text literal becomes one
+/// [`VText`] node containing exactly the literal's text, no matter how the literals and tags are
+/// laid out. Spaces that should end up in the document have to be part of a literal.
+///
+/// ```
+/// # use yew::prelude::*;
+/// # fn test() -> Html {
+/// // Renders `a b<b>c</b> d`
+/// html! { <>{"a"}{" "}{"b"} <b>{"c"}</b> {" d"}</> }
+/// # }
+/// ```
+///
+/// Each of these literals is rendered as its own text node. A fragment with
+/// `whitespace="coalesce"` joins adjacent literal children into a single text node instead, so the
+/// text is not split around whitespace literals. Expressions that are not literals are never
+/// joined. The default is `whitespace="preserve"`.
+///
+/// ```
+/// # use yew::prelude::*;
+/// # fn test() -> Html {
+/// // Renders the text nodes `a b` and ` d` around the `<b>` element
+/// html! { <whitespace="coalesce">{"a"}{" "}{"b"}<b>{"c"}</b>{" "}{"d"}</> }
+/// # }
+/// ```
+///
 /// More information about using the `html!` macro can be found in the [Yew Docs]
 ///
 /// [`Html`]: ./html/type.Html.html
 /// [`html_nested!`]: ./macro.html_nested.html
+/// [`VText`]: ./virtual_dom/struct.VText.html
 /// [Yew Docs]: https://yew.rs/docs/next/concepts/html
 pub use yew_macro::html;
 
@@ -269,6 +298,7 @@ pub mod context;
 pub mod functional;
 pub mod html;
 mod io_coop;
+pub mod progressive;
 pub mod scheduler;
 mod sealed;
 #[cfg(feature = "ssr")]
@@ -297,7 +327,7 @@ pub mod events {
 pub use crate::app_handle::AppHandle;
 use web_sys::Element;
 
-use crate::html::BaseComponent;
+use crate::html::{BaseComponent, Html, NodeRef};
 
 thread_local! {
     static PANIC_HOOK_IS_SET: Cell<bool> = Cell::new(false);
@@ -365,6 +395,80 @@ where
     )
 }
 
+/// Starts a Yew app mounted to the element `node_ref` points to.
+///
+/// This is useful for embedding an app into an element obtained elsewhere, e.g. rendered by
+/// another app. If you would like to pass props, use the `mount_to_with_props` method.
+///
+/// # Panics
+///
+/// Panics if `node_ref` is not set or does not point to an [Element].
+pub fn mount_to<COMP>(node_ref: &NodeRef) -> AppHandle<COMP>
+where
+    COMP: BaseComponent,
+    COMP::Properties: Default,
+{
+    mount_to_with_props(node_ref, COMP::Properties::default())
+}
+
+/// This function does the same as `mount_to(...)` but allows to start an Yew application with
+/// properties.
+///
+/// # Panics
+///
+/// Panics if `node_ref` is not set or does not point to an [Element].
+pub fn mount_to_with_props<COMP>(node_ref: &NodeRef, props: COMP::Properties) -> AppHandle<COMP>
+where
+    COMP: BaseComponent,
+{
+    let element = node_ref
+        .cast::<Element>()
+        .expect("node ref to mount to does not point to an element");
+    start_app_with_props_in_element(element, props)
+}
+
+/// Renders `node` offscreen, e.g. to measure content before positioning a tooltip or popover.
+///
+/// Returns the container the node was rendered into and a closure, which destroys the rendered
+/// content and removes the container. As layout is only computed for elements in the document,
+/// the container is appended to the body, but positioned outside of the viewport and hidden.
+///
+/// The content is rendered before returning, unless this is called while a component is
+/// rendering. In that case it is rendered right after the current render.
+pub fn render_detached(node: Html) -> (Element, impl FnOnce()) {
+    let container = gloo_utils::document()
+        .create_element("div")
+        .expect("can't create element for detached content");
+    container
+        .set_attribute(
+            "style",
+            "position: absolute; top: 0; left: -100000px; visibility: hidden;",
+        )
+        .expect("can't set style of detached content");
+    gloo_utils::document()
+        .body()
+        .expect("no body node found")
+        .append_child(&container)
+        .expect("can't append detached content");
+
+    let app = start_app_with_props_in_element::<app_handle::Detached>(
+        container.clone(),
+        app_handle::DetachedProps { node },
+    );
+    scheduler::start_now();
+
+    let cleanup = {
+        let container = container.clone();
+        move || {
+            app.destroy();
+            scheduler::start_now();
+            container.remove();
+        }
+    };
+
+    (container, cleanup)
+}
+
 /// The Yew Prelude
 ///
 /// The purpose of this module is to alleviate imports of many common types:
@@ -379,8 +483,8 @@ pub mod prelude {
     pub use crate::context::{ContextHandle, ContextProvider};
     pub use crate::events::*;
     pub use crate::html::{
-        create_portal, BaseComponent, Children, ChildrenWithProps, Classes, Component, Context,
-        Html, HtmlResult, NodeRef, Properties,
+        create_portal, create_shadow_portal, BaseComponent, Children, ChildrenWithProps, Classes,
+        Component, Context, Html, HtmlResult, NodeRef, Properties,
     };
     pub use crate::macros::{classes, html, html_nested};
     pub use crate::suspense::Suspense;
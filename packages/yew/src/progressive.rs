@@ -0,0 +1,111 @@
+//! This module defines the `ProgressiveList` component.
+
+use crate::virtual_dom::{Key, VList};
+use crate::{Children, Component, Context, Html, Properties};
+use gloo::render::{request_animation_frame, AnimationFrame};
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+/// Props for [`ProgressiveList`]
+#[derive(Debug, Clone, PartialEq, Properties)]
+pub struct ProgressiveListProps {
+    /// Number of children added per animation frame
+    #[prop_or(100)]
+    pub chunk_size: usize,
+    /// Whether changed children are applied in chunks as well, instead of all at once
+    #[prop_or(false)]
+    pub slice_updates: bool,
+    /// Children
+    #[prop_or_default]
+    pub children: Children,
+}
+
+/// A list which renders its children progressively.
+///
+/// The first `chunk_size` children are rendered immediately, the rest are appended in chunks
+/// of `chunk_size` across subsequent animation frames. This keeps the main thread responsive
+/// when a very large list is rendered for the first time. Once all chunks have been added, the
+/// result is identical to rendering the children at once.
+///
+/// With `slice_updates`, changing the children is spread across animation frames the same way,
+/// e.g. when switching between large data sets. The changed children replace the previously
+/// shown ones chunk by chunk. If the children change again before all chunks were applied, the
+/// slicing restarts from what is currently shown.
+pub struct ProgressiveList {
+    rendered: usize,
+    frame: Option<AnimationFrame>,
+    /// Children shown by the last render
+    shown: RefCell<Vec<Html>>,
+    /// Previously shown children, which are replaced chunk by chunk
+    stale: Vec<Html>,
+}
+
+impl ProgressiveList {
+    fn chunk_size(ctx: &Context<Self>) -> usize {
+        ctx.props().chunk_size.max(1)
+    }
+}
+
+impl Component for ProgressiveList {
+    type Message = ();
+    type Properties = ProgressiveListProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            rendered: Self::chunk_size(ctx),
+            frame: None,
+            shown: RefCell::default(),
+            stale: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, _msg: Self::Message) -> bool {
+        self.frame = None;
+        self.rendered += Self::chunk_size(ctx);
+        if self.rendered >= self.stale.len() {
+            self.stale.clear();
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        if ctx.props().slice_updates {
+            self.stale = self.shown.take();
+            self.rendered = Self::chunk_size(ctx);
+        } else {
+            // Children which are already rendered stay rendered
+            self.rendered = self.rendered.max(Self::chunk_size(ctx));
+            self.stale.clear();
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let mut children: Vec<Html> = ctx.props().children.iter().take(self.rendered).collect();
+        if self.stale.len() > self.rendered {
+            // Stale children with keys, that are already shown, would be duplicates
+            let keys: HashSet<Key> = children.iter().filter_map(Html::key).collect();
+            let stale = self.stale[self.rendered..]
+                .iter()
+                .filter(|child| child.key().map_or(true, |key| !keys.contains(&key)))
+                .cloned();
+            children.extend(stale);
+        }
+        if ctx.props().slice_updates {
+            *self.shown.borrow_mut() = children.clone();
+        }
+
+        VList::with_children(children, None).into()
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        if self.rendered < ctx.props().children.len() || !self.stale.is_empty() {
+            if self.frame.is_none() {
+                let link = ctx.link().clone();
+                self.frame = Some(request_animation_frame(move |_| link.send_message(())));
+            }
+        } else {
+            self.frame = None;
+        }
+    }
+}
@@ -112,6 +112,11 @@ pub(crate) fn start_now() {
             loop {
                 with(|s| s.fill_queue(&mut queue));
                 if queue.is_empty() {
+                    // The commit hooks might schedule more work
+                    #[cfg(feature = "render_hooks")]
+                    if crate::app_handle::render_commit() {
+                        continue;
+                    }
                     break;
                 }
                 for r in queue.drain(..) {
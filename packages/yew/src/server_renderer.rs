@@ -1,6 +1,79 @@
 use super::*;
 
-use crate::html::Scope;
+use crate::context::{ContextProvider, ContextProviderProps};
+use crate::html::{RenderResult, Scope};
+use crate::virtual_dom::{RenderOptions, VComp};
+use futures::stream::{self, LocalBoxStream, StreamExt};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+/// Collects state during server-side rendering, which is serialized alongside the markup.
+///
+/// It is provided as a context by [`ServerRenderer::render_with_state`]. Components record
+/// their state as JSON, so the client can resume from it without fetching it again.
+#[cfg_attr(documenting, doc(cfg(feature = "ssr")))]
+#[derive(Debug, Clone, Default)]
+pub struct StateCollector {
+    entries: Rc<RefCell<BTreeMap<String, String>>>,
+}
+
+impl PartialEq for StateCollector {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.entries, &other.entries)
+    }
+}
+
+impl StateCollector {
+    /// Records `json` under `key`, replacing any previous value.
+    ///
+    /// `json` has to be a valid JSON value, e.g. produced by `serde_json::to_string`.
+    pub fn insert(&self, key: impl Into<String>, json: impl Into<String>) {
+        self.entries.borrow_mut().insert(key.into(), json.into());
+    }
+
+    /// Serializes the recorded state into a JSON object
+    ///
+    /// The object is safe to embed in a `<script>` tag, as `<` and the line terminators U+2028
+    /// and U+2029 are escaped in both keys and values.
+    fn to_json(&self) -> String {
+        let mut s = String::from("{");
+        for (i, (key, json)) in self.entries.borrow().iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            s.push('"');
+            for c in key.chars() {
+                match c {
+                    '"' => s.push_str("\\\""),
+                    '\\' => s.push_str("\\\\"),
+                    c if c.is_control() => {
+                        let _ = write!(s, "\\u{:04x}", c as u32);
+                    }
+                    c => push_script_safe(&mut s, c),
+                }
+            }
+            s.push_str("\":");
+            // These characters can only occur in strings of valid JSON, where they can be escaped
+            for c in json.chars() {
+                push_script_safe(&mut s, c);
+            }
+        }
+        s.push('}');
+        s
+    }
+}
+
+/// Pushes `c`, escaping the characters which could end a `<script>` tag or break its JavaScript
+fn push_script_safe(s: &mut String, c: char) {
+    match c {
+        '<' => s.push_str("\\u003c"),
+        '\u{2028}' => s.push_str("\\u2028"),
+        '\u{2029}' => s.push_str("\\u2029"),
+        c => s.push(c),
+    }
+}
 
 /// A Yew Server-side Renderer.
 #[cfg_attr(documenting, doc(cfg(feature = "ssr")))]
@@ -10,6 +83,8 @@ where
     COMP: BaseComponent,
 {
     props: COMP::Properties,
+    hydratable: bool,
+    pretty: bool,
 }
 
 impl<COMP> Default for ServerRenderer<COMP>
@@ -39,10 +114,53 @@ where
 {
     /// Creates a [ServerRenderer] with custom properties.
     pub fn with_props(props: COMP::Properties) -> Self {
-        Self { props }
+        Self {
+            props,
+            hydratable: false,
+            pretty: false,
+        }
+    }
+
+    /// Sets whether the rendered markup should contain the information needed to hydrate it.
+    ///
+    /// When enabled, consecutive text nodes are separated by an empty comment (`<!---->`), so
+    /// their boundaries can be restored on the client where the browser would otherwise parse
+    /// them into a single text node.
+    ///
+    /// Defaults to `false`.
+    pub fn hydratable(mut self, val: bool) -> Self {
+        self.hydratable = val;
+
+        self
+    }
+
+    /// Sets whether the rendered markup should be indented for readability, e.g. for debugging
+    /// or snapshot tests.
+    ///
+    /// When enabled, siblings are put on separate lines and the children of elements are
+    /// indented by two spaces. As this adds whitespace to the document, the markup should not
+    /// be used for hydration.
+    ///
+    /// Defaults to `false`.
+    pub fn pretty(mut self, val: bool) -> Self {
+        self.pretty = val;
+
+        self
+    }
+
+    fn options(&self) -> RenderOptions {
+        RenderOptions {
+            hydratable: self.hydratable,
+            pretty: self.pretty,
+            depth: 0,
+            reject_duplicate_keys: false,
+        }
     }
 
     /// Renders Yew Application.
+    ///
+    /// Duplicate keys in keyed lists are rendered as they are. Use [`try_render`](Self::try_render)
+    /// to reject them.
     pub async fn render(self) -> String {
         let mut s = String::new();
 
@@ -53,7 +171,203 @@ where
 
     /// Renders Yew Application to a String.
     pub async fn render_to_string(self, w: &mut String) {
+        let options = self.options();
+        let scope = Scope::<COMP>::new(None);
+        // Rendering only fails on duplicate keys, which are not rejected with these options
+        let _ = scope.render_to_string(w, self.props.into(), options).await;
+    }
+
+    /// Renders Yew Application, returning an error if rendering fails.
+    ///
+    /// Rendering fails with [`RenderError::DuplicateKey`](crate::html::RenderError::DuplicateKey)
+    /// if a keyed list contains the same key more than once.
+    pub async fn try_render(self) -> RenderResult<String> {
+        let mut s = String::new();
+
+        self.try_render_to_string(&mut s).await?;
+
+        Ok(s)
+    }
+
+    /// Renders Yew Application to a String, returning an error if rendering fails, see
+    /// [`try_render`](Self::try_render).
+    pub async fn try_render_to_string(self, w: &mut String) -> RenderResult<()> {
+        let options = RenderOptions {
+            reject_duplicate_keys: true,
+            ..self.options()
+        };
         let scope = Scope::<COMP>::new(None);
-        scope.render_to_string(w, self.props.into()).await;
+        scope.render_to_string(w, self.props.into(), options).await
+    }
+
+    /// Renders Yew Application and the state recorded into the [`StateCollector`] context.
+    ///
+    /// Returns the markup and the recorded state serialized as a JSON object.
+    pub async fn render_with_state(self) -> (String, String) {
+        let options = self.options();
+        let state = StateCollector::default();
+        let props = ContextProviderProps {
+            context: state.clone(),
+            children: Children::new(vec![VComp::new::<COMP>(
+                self.props.into(),
+                NodeRef::default(),
+                None,
+            )
+            .into()]),
+        };
+
+        let mut s = String::new();
+        // Rendering only fails on duplicate keys, which are not rejected with these options
+        let _ = Scope::<ContextProvider<StateCollector>>::new(None)
+            .render_to_string(&mut s, props.into(), options)
+            .await;
+
+        (s, state.to_json())
+    }
+
+    /// Turns the renderer into a response body stream.
+    ///
+    /// The stream yields a single chunk with the whole markup once rendering finished, or the
+    /// error rendering failed with. It can be used as the response body in web servers, after
+    /// converting the chunk into the body's byte type (e.g. `Bytes::from`).
+    pub fn into_body(self) -> LocalBoxStream<'static, RenderResult<String>> {
+        stream::once(self.try_render()).boxed_local()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod ssr_tests {
+    use futures::StreamExt;
+    use tokio::test;
+
+    use crate::prelude::*;
+    use crate::{ServerRenderer, StateCollector};
+
+    #[function_component]
+    fn Comp() -> Html {
+        html! {
+            <div>
+                <p>{"Hello, "}{"world!"}</p>
+                <ul>{ for (0..3).map(|i| html! { <li key={i}>{ i }</li> }) }</ul>
+            </div>
+        }
+    }
+
+    #[test]
+    async fn test_render_with_state() {
+        #[function_component]
+        fn User() -> Html {
+            let state = use_context::<StateCollector>().unwrap();
+            state.insert("user", r#"{"name":"Yew","id":1}"#);
+            state.insert("a \"quoted\" </key>", "[]");
+
+            html! { <p>{"Yew"}</p> }
+        }
+
+        let (markup, state) = ServerRenderer::<User>::new().render_with_state().await;
+
+        assert_eq!(markup, "<p>Yew</p>");
+        assert_eq!(
+            state,
+            r#"{"a \"quoted\" \u003c/key>":[],"user":{"name":"Yew","id":1}}"#
+        );
+    }
+
+    #[test]
+    async fn test_render_with_state_escapes_values() {
+        #[function_component]
+        fn Comment() -> Html {
+            let state = use_context::<StateCollector>().unwrap();
+            state.insert("comment", "\"</script><script>alert(1)</script>\"");
+            state.insert("lines", "\"a\u{2028}b\u{2029}c\"");
+
+            html! {}
+        }
+
+        let (_, state) = ServerRenderer::<Comment>::new().render_with_state().await;
+
+        assert!(!state.contains("</script>"));
+        assert_eq!(
+            state,
+            r#"{"comment":"\u003c/script>\u003cscript>alert(1)\u003c/script>","lines":"a\u2028b\u2029c"}"#
+        );
+    }
+
+    #[test]
+    async fn test_pretty() {
+        #[function_component]
+        fn Nested() -> Html {
+            html! {
+                <>
+                    <div>
+                        <p>{"Hello"}</p>
+                        <>
+                            <span></span>
+                            <input />
+                        </>
+                    </div>
+                    <footer>{"Yew"}</footer>
+                </>
+            }
+        }
+
+        let minified = ServerRenderer::<Nested>::new().render().await;
+        assert_eq!(
+            minified,
+            "<div><p>Hello</p><span></span><input></div><footer>Yew</footer>"
+        );
+
+        let pretty = ServerRenderer::<Nested>::new().pretty(true).render().await;
+        assert_eq!(
+            pretty,
+            r#"<div>
+  <p>
+    Hello
+  </p>
+  <span></span>
+  <input>
+</div>
+<footer>
+  Yew
+</footer>"#
+        );
+    }
+
+    #[test]
+    async fn test_pretty_preformatted() {
+        #[function_component]
+        fn Preformatted() -> Html {
+            html! {
+                <div>
+                    <pre>{"fn main() {\n    yew();\n}"}<b>{"!"}</b></pre>
+                    <textarea value="a\n  b" />
+                    <script>{"let a = 1;\nlet b = 2;"}</script>
+                    <style>{"p {\n  margin: 0;\n}"}</style>
+                </div>
+            }
+        }
+
+        let pretty = ServerRenderer::<Preformatted>::new()
+            .pretty(true)
+            .render()
+            .await;
+        assert_eq!(
+            pretty,
+            "<div>\n  \
+             <pre>fn main() {\n    yew();\n}<b>!</b></pre>\n  \
+             <textarea>a\n  b</textarea>\n  \
+             <script>let a = 1;\nlet b = 2;</script>\n  \
+             <style>p {\n  margin: 0;\n}</style>\n\
+             </div>"
+        );
+    }
+
+    #[test]
+    async fn test_into_body() {
+        let expected = ServerRenderer::<Comp>::new().render().await;
+
+        let mut body = ServerRenderer::<Comp>::new().into_body();
+        assert_eq!(body.next().await.unwrap().unwrap(), expected);
+        assert!(body.next().await.is_none());
     }
 }
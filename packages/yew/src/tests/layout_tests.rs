@@ -35,27 +35,82 @@ pub struct TestLayout<'a> {
     pub expected: &'a str,
 }
 
+/// Sentinel nodes surrounding the rendered layout
+struct Boundaries<'a> {
+    parent: &'a Node,
+    start: &'a Node,
+    end: &'a Node,
+}
+
+impl Boundaries<'_> {
+    /// Asserts that the layout was rendered right between the sentinels and that `first`,
+    /// as returned by [`VDiff::apply`], points to its first node.
+    ///
+    /// A wrong `first` corrupts the placement of preceding siblings, which reuse it as their
+    /// `next_sibling`.
+    fn check(&self, first: &NodeRef, step: &str, name: &str) {
+        assert_eq!(
+            self.parent.first_child().as_ref(),
+            Some(self.start),
+            "{} moved nodes before the fragment for layout '{}'",
+            step,
+            name,
+        );
+        assert_eq!(
+            self.parent.last_child().as_ref(),
+            Some(self.end),
+            "{} moved nodes after the fragment for layout '{}'",
+            step,
+            name,
+        );
+        assert_eq!(
+            first.get(),
+            self.start.next_sibling(),
+            "{} returned the wrong first node for layout '{}'",
+            step,
+            name,
+        );
+    }
+}
+
+/// Apply each layout independently, then the whole sequence of layouts forwards and backwards
+/// over the previous one, asserting the rendered HTML after every step.
+///
+/// Running the sequence in both directions covers each transition as well as its reverse, e.g.
+/// both adding and removing a node, which catches asymmetric reconciliation bugs.
+///
+/// The layouts are rendered between a `START` and an `END` sentinel node, which are checked to
+/// stay adjacent to the rendered fragment after every step.
 pub fn diff_layouts(layouts: Vec<TestLayout<'_>>) {
     let document = gloo_utils::document();
     let parent_scope: AnyScope = Scope::<Comp>::new(None).into();
     let parent_element = document.create_element("div").unwrap();
     let parent_node: Node = parent_element.clone().into();
+    let start_node: Node = document.create_text_node("START").into();
+    parent_node.append_child(&start_node).unwrap();
     let end_node = document.create_text_node("END");
     parent_node.append_child(&end_node).unwrap();
+    let end_node: Node = end_node.into();
+    let boundaries = Boundaries {
+        parent: &parent_node,
+        start: &start_node,
+        end: &end_node,
+    };
     let mut empty_node: VNode = VText::new("").into();
 
     // Tests each layout independently
-    let next_sibling = NodeRef::new(end_node.into());
+    let next_sibling = NodeRef::new(end_node.clone());
     for layout in layouts.iter() {
         // Apply the layout
         let mut node = layout.node.clone();
         log!("Independently apply layout '{}'", layout.name);
 
-        node.apply(&parent_scope, &parent_element, next_sibling.clone(), None);
+        let first = node.apply(&parent_scope, &parent_element, next_sibling.clone(), None);
         scheduler::start_now();
+        boundaries.check(&first, "Independent apply", layout.name);
         assert_eq!(
             parent_element.inner_html(),
-            format!("{}END", layout.expected),
+            format!("START{}END", layout.expected),
             "Independent apply failed for layout '{}'",
             layout.name,
         );
@@ -65,31 +120,33 @@ pub fn diff_layouts(layouts: Vec<TestLayout<'_>>) {
 
         log!("Independently reapply layout '{}'", layout.name);
 
-        node_clone.apply(
+        let first = node_clone.apply(
             &parent_scope,
             &parent_element,
             next_sibling.clone(),
             Some(node),
         );
         scheduler::start_now();
+        boundaries.check(&first, "Independent reapply", layout.name);
         assert_eq!(
             parent_element.inner_html(),
-            format!("{}END", layout.expected),
+            format!("START{}END", layout.expected),
             "Independent reapply failed for layout '{}'",
             layout.name,
         );
 
         // Detach
-        empty_node.clone().apply(
+        let first = empty_node.clone().apply(
             &parent_scope,
             &parent_element,
             next_sibling.clone(),
             Some(node_clone),
         );
         scheduler::start_now();
+        boundaries.check(&first, "Independent detach", layout.name);
         assert_eq!(
             parent_element.inner_html(),
-            "END",
+            "STARTEND",
             "Independent detach failed for layout '{}'",
             layout.name,
         );
@@ -101,16 +158,17 @@ pub fn diff_layouts(layouts: Vec<TestLayout<'_>>) {
         let mut next_node = layout.node.clone();
 
         log!("Sequentially apply layout '{}'", layout.name);
-        next_node.apply(
+        let first = next_node.apply(
             &parent_scope,
             &parent_element,
             next_sibling.clone(),
             ancestor,
         );
         scheduler::start_now();
+        boundaries.check(&first, "Sequential apply", layout.name);
         assert_eq!(
             parent_element.inner_html(),
-            format!("{}END", layout.expected),
+            format!("START{}END", layout.expected),
             "Sequential apply failed for layout '{}'",
             layout.name,
         );
@@ -122,16 +180,17 @@ pub fn diff_layouts(layouts: Vec<TestLayout<'_>>) {
         let mut next_node = layout.node.clone();
 
         log!("Sequentially detach layout '{}'", layout.name);
-        next_node.apply(
+        let first = next_node.apply(
             &parent_scope,
             &parent_element,
             next_sibling.clone(),
             ancestor,
         );
         scheduler::start_now();
+        boundaries.check(&first, "Sequential detach", layout.name);
         assert_eq!(
             parent_element.inner_html(),
-            format!("{}END", layout.expected),
+            format!("START{}END", layout.expected),
             "Sequential detach failed for layout '{}'",
             layout.name,
         );
@@ -139,11 +198,12 @@ pub fn diff_layouts(layouts: Vec<TestLayout<'_>>) {
     }
 
     // Detach last layout
-    empty_node.apply(&parent_scope, &parent_element, next_sibling, ancestor);
+    let first = empty_node.apply(&parent_scope, &parent_element, next_sibling, ancestor);
     scheduler::start_now();
+    boundaries.check(&first, "Final detach", "");
     assert_eq!(
         parent_element.inner_html(),
-        "END",
+        "STARTEND",
         "Failed to detach last layout"
     );
 }
@@ -55,6 +55,12 @@ impl<IN, OUT> IntoIterator for NodeSeq<IN, OUT> {
 #[doc(hidden)]
 pub fn __ensure_type<T>(_: T) {}
 
+/// Boolean attributes are set for `true` and omitted for `false` or `None`.
+#[doc(hidden)]
+pub fn __is_boolean_attr_set(value: impl Into<Option<bool>>) -> bool {
+    value.into().unwrap_or(false)
+}
+
 /// Print the [web_sys::Node]'s contents as a string for debugging purposes
 pub fn print_node(n: &web_sys::Node) -> String {
     use wasm_bindgen::JsCast;
@@ -1,16 +1,236 @@
 //! This module contains the implementation yew's virtual nodes' keys.
 
 use crate::html::ImplicitClone;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 
 /// Represents the (optional) key of Yew's virtual nodes.
 ///
 /// Keys are cheap to clone.
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+///
+/// Keys created from numeric types never compare equal to keys created from strings, even if
+/// their textual representation is the same. `Key::from(1)` and `Key::from("1")` are treated as
+/// different keys.
+///
+/// With the `intern_keys` feature, keys created from strings share their storage with all other
+/// live keys created from the same string on the current thread.
+///
+/// Keys are totally ordered, so they can be used in sorted containers like a `BTreeMap`. Numeric
+/// keys are ordered numerically and sort before string keys, which are ordered lexically by their
+/// bytes. Keys created with [`Key::from_hashable`] sort last in an unspecified, but consistent
+/// order.
+#[derive(Clone, Debug, Eq)]
 pub struct Key {
     key: Rc<str>,
+    kind: KeyKind,
+    /// The value a key of kind [KeyKind::Hashed] was created from
+    value: Option<Rc<dyn HashedValue>>,
+}
+
+/// A value of any type, which can be compared to values of other types
+trait HashedValue {
+    fn as_any(&self) -> &dyn Any;
+    fn eq_value(&self, other: &dyn HashedValue) -> bool;
+}
+
+impl<T: Eq + 'static> HashedValue for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_value(&self, other: &dyn HashedValue) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<T>()
+            .map_or(false, |other| self == other)
+    }
+}
+
+impl fmt::Debug for dyn HashedValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("HashedValue")
+    }
+}
+
+/// The values of live hashed keys, by their type and hash.
+///
+/// Equal values share a single allocation, so keys whose hashes collide can be ordered by its
+/// address consistently with their equality.
+#[derive(Default)]
+struct HashedValues {
+    entries: HashMap<(TypeId, u64), Vec<Weak<dyn HashedValue>>>,
+    /// Number of entries, above which entries without live values are removed
+    prune_at: usize,
+}
+
+impl HashedValues {
+    fn get_or_insert<T: Eq + Clone + 'static>(
+        &mut self,
+        hash: u64,
+        value: &T,
+    ) -> Rc<dyn HashedValue> {
+        let values = self.entries.entry((TypeId::of::<T>(), hash)).or_default();
+        values.retain(|value| value.strong_count() > 0);
+        if let Some(shared) = values
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|shared| value.eq_value(&**shared))
+        {
+            return shared;
+        }
+
+        let shared: Rc<dyn HashedValue> = Rc::new(value.clone());
+        values.push(Rc::downgrade(&shared));
+
+        if self.entries.len() > self.prune_at {
+            self.entries.retain(|_, values| {
+                values.retain(|value| value.strong_count() > 0);
+                !values.is_empty()
+            });
+            self.prune_at = std::cmp::max(self.entries.len() * 2, 64);
+        }
+        shared
+    }
+}
+
+thread_local! {
+    static HASHED_VALUES: RefCell<HashedValues> = RefCell::new(HashedValues::default());
+}
+
+/// The kind of value a [Key] was created from
+#[derive(Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+enum KeyKind {
+    Str,
+    Numeric,
+    /// Created from a hashable value of the contained type
+    Hashed(TypeId),
+}
+
+impl Key {
+    /// Creates a key from any hashable value, such as a tuple of ids or a struct.
+    ///
+    /// This avoids having to stringify composite identities. A clone of `value` is stored
+    /// alongside its hash, so keys only compare equal if their values do, even if the hashes of
+    /// different values collide. Keys created from values of different types never compare equal.
+    /// The string form of such a key is the hash of its value. Keys created from equal values on
+    /// the current thread share the stored clone.
+    ///
+    /// # Example
+    /// ```
+    /// # use yew::prelude::*;
+    /// # use yew::virtual_dom::Key;
+    /// # fn test(group_id: u32, item_id: u32) -> Html {
+    /// html! {
+    ///     <li key={Key::from_hashable(&(group_id, item_id))}></li>
+    /// }
+    /// # }
+    /// ```
+    pub fn from_hashable<T: Hash + Eq + Clone + 'static>(value: &T) -> Self {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        Self {
+            key: Rc::from(format!("{:016x}", hash).as_str()),
+            kind: KeyKind::Hashed(TypeId::of::<T>()),
+            value: Some(HASHED_VALUES.with(|v| v.borrow_mut().get_or_insert(hash, value))),
+        }
+    }
+
+    /// Returns the string form of the key, e.g. for persisting state by key.
+    ///
+    /// Keys created from strings are reconstructed from it with [`Key::from`]. Numeric keys
+    /// return their decimal representation, which has to be parsed back into the number, as
+    /// string and numeric keys never compare equal.
+    pub fn as_str(&self) -> &str {
+        &self.key
+    }
+
+    /// Compares the values hashed keys were created from
+    fn value_eq(&self, other: &Self) -> bool {
+        match (&self.value, &other.value) {
+            (Some(a), Some(b)) => a.eq_value(&**b),
+            _ => true,
+        }
+    }
+
+    /// Orders hashed keys created from different values with colliding hashes.
+    ///
+    /// The values are not ordered themselves, but by the address of their stored clones. Equal
+    /// values share their clone, so the order is consistent with `Eq` for as long as the keys
+    /// are alive.
+    fn cmp_colliding(&self, other: &Self) -> Ordering {
+        match (&self.value, &other.value) {
+            (Some(a), Some(b)) if !self.value_eq(other) => {
+                let addr = |value: &Rc<dyn HashedValue>| Rc::as_ptr(value) as *const () as usize;
+                addr(a).cmp(&addr(b))
+            }
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        // Interned keys can be compared by pointer
+        self.kind == other.kind
+            && (Rc::ptr_eq(&self.key, &other.key) || self.key == other.key)
+            && self.value_eq(other)
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        /// Position of each kind in the order across kinds
+        fn rank(kind: &KeyKind) -> u8 {
+            match kind {
+                KeyKind::Numeric => 0,
+                KeyKind::Str => 1,
+                KeyKind::Hashed(_) => 2,
+            }
+        }
+
+        /// Orders the decimal representations of two integers numerically
+        fn cmp_numeric(a: &str, b: &str) -> Ordering {
+            match (a.strip_prefix('-'), b.strip_prefix('-')) {
+                (Some(a), Some(b)) => cmp_numeric(b, a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                // Without leading zeros, longer numbers are greater
+                (None, None) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+            }
+        }
+
+        match (&self.kind, &other.kind) {
+            (KeyKind::Numeric, KeyKind::Numeric) => cmp_numeric(&self.key, &other.key),
+            (KeyKind::Str, KeyKind::Str) => self.key.cmp(&other.key),
+            (KeyKind::Hashed(a), KeyKind::Hashed(b)) => a
+                .cmp(b)
+                .then_with(|| self.key.cmp(&other.key))
+                .then_with(|| self.cmp_colliding(other)),
+            (a, b) => rank(a).cmp(&rank(b)),
+        }
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+        self.kind.hash(state);
+    }
 }
 
 impl Display for Key {
@@ -29,17 +249,93 @@ impl Deref for Key {
 
 impl From<Rc<str>> for Key {
     fn from(key: Rc<str>) -> Self {
-        Self { key }
+        Self {
+            key,
+            kind: KeyKind::Str,
+            value: None,
+        }
     }
 }
 
 impl From<&'_ str> for Key {
     fn from(key: &'_ str) -> Self {
+        #[cfg(feature = "intern_keys")]
+        let key = feat_intern::intern(key);
+        #[cfg(not(feature = "intern_keys"))]
         let key: Rc<str> = Rc::from(key);
         Self::from(key)
     }
 }
 
+#[cfg(feature = "intern_keys")]
+mod feat_intern {
+    use std::cell::RefCell;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::hash::{Hash, Hasher};
+    use std::rc::{Rc, Weak};
+
+    thread_local! {
+        static INTERNED: RefCell<Interner> = RefCell::new(Interner::default());
+    }
+
+    /// Weak references to the storage of string keys, by the hash of the string.
+    ///
+    /// Strings are freed once no key uses them anymore, their entries are pruned on insert.
+    #[derive(Default)]
+    struct Interner {
+        entries: HashMap<u64, Vec<Weak<str>>>,
+        /// Number of entries, including the ones of freed strings
+        len: usize,
+        /// Number of entries after which the entries of freed strings are pruned
+        prune_at: usize,
+    }
+
+    impl Interner {
+        fn intern(&mut self, key: &str) -> Rc<str> {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            let entries = self.entries.entry(hasher.finish()).or_default();
+            if let Some(key) = entries
+                .iter()
+                .filter_map(Weak::upgrade)
+                .find(|k| &**k == key)
+            {
+                return key;
+            }
+
+            let key: Rc<str> = Rc::from(key);
+            entries.push(Rc::downgrade(&key));
+            self.len += 1;
+            if self.len > self.prune_at {
+                self.prune();
+            }
+            key
+        }
+
+        fn prune(&mut self) {
+            self.entries.retain(|_, entries| {
+                entries.retain(|k| k.strong_count() > 0);
+                !entries.is_empty()
+            });
+            self.len = self.entries.values().map(Vec::len).sum();
+            // Prune again once the entries doubled, which keeps inserting amortized O(1)
+            self.prune_at = (self.len * 2).max(64);
+        }
+    }
+
+    /// Returns the shared storage of `key`, allocating it if no key uses it
+    pub(super) fn intern(key: &str) -> Rc<str> {
+        INTERNED.with(|interned| interned.borrow_mut().intern(key))
+    }
+
+    /// Returns the number of entries in the interner
+    #[cfg(test)]
+    pub(super) fn len() -> usize {
+        INTERNED.with(|interned| interned.borrow().len)
+    }
+}
+
 impl ImplicitClone for Key {}
 
 macro_rules! key_impl_from_to_string {
@@ -54,22 +350,32 @@ macro_rules! key_impl_from_to_string {
 
 key_impl_from_to_string!(String);
 key_impl_from_to_string!(char);
-key_impl_from_to_string!(u8);
-key_impl_from_to_string!(u16);
-key_impl_from_to_string!(u32);
-key_impl_from_to_string!(u64);
-key_impl_from_to_string!(u128);
-key_impl_from_to_string!(usize);
-key_impl_from_to_string!(i8);
-key_impl_from_to_string!(i16);
-key_impl_from_to_string!(i32);
-key_impl_from_to_string!(i64);
-key_impl_from_to_string!(i128);
-key_impl_from_to_string!(isize);
+
+macro_rules! key_impl_from_numeric {
+    ($($type:ty)*) => {
+        $(
+            impl From<$type> for Key {
+                fn from(key: $type) -> Self {
+                    Self {
+                        key: Rc::from(key.to_string().as_str()),
+                        kind: KeyKind::Numeric,
+                        value: None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+key_impl_from_numeric!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
 
 #[cfg(test)]
 mod test {
+    use super::Key;
     use crate::html;
+    use std::cmp::Ordering;
+    use std::collections::BTreeMap;
+    use std::hash::{Hash, Hasher};
     use std::rc::Rc;
 
     #[cfg(feature = "wasm_test")]
@@ -100,4 +406,168 @@ mod test {
             </>
         };
     }
+
+    #[test]
+    fn numeric_and_string_keys_differ() {
+        assert_ne!(Key::from(1_usize), Key::from("1"));
+        assert_ne!(Key::from(1_u8), Key::from("1".to_string()));
+        assert_eq!(Key::from(1_usize), Key::from(1_i32));
+        assert_eq!(&*Key::from(1_usize), &*Key::from("1"));
+    }
+
+    #[test]
+    fn string_form_round_trips() {
+        let key = Key::from("row-7");
+        assert_eq!(key.as_str(), "row-7");
+        assert_eq!(Key::from(key.as_str()), key);
+
+        let key = Key::from(42_u32);
+        assert_eq!(key.as_str(), "42");
+        assert_eq!(key.to_string(), "42");
+        assert_eq!(Key::from(key.as_str().parse::<u32>().unwrap()), key);
+    }
+
+    #[test]
+    fn hashable_keys() {
+        assert_eq!(
+            Key::from_hashable(&(1_u32, 2_u32)),
+            Key::from_hashable(&(1_u32, 2_u32))
+        );
+        assert_ne!(
+            Key::from_hashable(&(1_u32, 2_u32)),
+            Key::from_hashable(&(2_u32, 1_u32))
+        );
+        // Same values, but different types
+        assert_ne!(
+            Key::from_hashable(&(1_u32, 2_u32)),
+            Key::from_hashable(&(1_u64, 2_u64))
+        );
+
+        let hashed = Key::from_hashable(&"a");
+        assert_ne!(hashed, Key::from(&*hashed));
+    }
+
+    /// Hashes all values to the same hash
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Colliding(u32);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, _state: &mut H) {}
+    }
+
+    #[test]
+    fn hashable_keys_with_colliding_hashes() {
+        let a = Key::from_hashable(&Colliding(1));
+        let b = Key::from_hashable(&Colliding(2));
+        assert_eq!(a.as_str(), b.as_str());
+        assert_ne!(a, b);
+        assert_ne!(a.cmp(&b), Ordering::Equal);
+        assert_eq!(a, Key::from_hashable(&Colliding(1)));
+    }
+
+    #[test]
+    fn colliding_keys_are_ordered_consistently() {
+        let a1 = Key::from_hashable(&Colliding(1));
+        let a2 = Key::from_hashable(&Colliding(1));
+        let b = Key::from_hashable(&Colliding(2));
+
+        // Keys built separately from equal values order the same against a third key
+        assert_eq!(a1.cmp(&a2), Ordering::Equal);
+        assert_eq!(a1.cmp(&b), a2.cmp(&b));
+        assert_eq!(b.cmp(&a1), b.cmp(&a2));
+
+        let map: BTreeMap<Key, ()> = vec![(a1.clone(), ()), (b.clone(), ()), (a2, ())]
+            .into_iter()
+            .collect();
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&a1) && map.contains_key(&b));
+    }
+
+    #[test]
+    fn ordered_in_btree_map() {
+        let numeric: BTreeMap<Key, ()> = [10_i32, -3, 2, 0, -20, 100]
+            .iter()
+            .map(|&k| (Key::from(k), ()))
+            .collect();
+        let order: Vec<_> = numeric.keys().map(Key::as_str).collect();
+        assert_eq!(order, ["-20", "-3", "0", "2", "10", "100"]);
+
+        let strings: BTreeMap<Key, ()> = ["b", "a10", "a9", "B"]
+            .iter()
+            .map(|&k| (Key::from(k), ()))
+            .collect();
+        let order: Vec<_> = strings.keys().map(Key::as_str).collect();
+        assert_eq!(order, ["B", "a10", "a9", "b"]);
+
+        // Numeric keys sort before string keys, even with the same string form
+        let mixed: BTreeMap<Key, ()> = vec![
+            (Key::from("1"), ()),
+            (Key::from(2_u8), ()),
+            (Key::from("0"), ()),
+            (Key::from(1_u64), ()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            mixed.keys().cloned().collect::<Vec<_>>(),
+            [
+                Key::from(1_u64),
+                Key::from(2_u8),
+                Key::from("0"),
+                Key::from("1")
+            ]
+        );
+    }
+
+    #[test]
+    fn order_is_consistent_with_eq() {
+        let keys = [
+            Key::from(1_u8),
+            Key::from(1_i64),
+            Key::from("1"),
+            Key::from(-1_i8),
+            Key::from_hashable(&1_u8),
+            Key::from_hashable(&1_u16),
+            Key::from_hashable(&Colliding(1)),
+            Key::from_hashable(&Colliding(2)),
+        ];
+        for a in &keys {
+            for b in &keys {
+                assert_eq!(a.cmp(b) == Ordering::Equal, a == b, "{:?} {:?}", a, b);
+                assert_eq!(a.cmp(b), b.cmp(a).reverse());
+            }
+        }
+    }
+
+    #[cfg(feature = "intern_keys")]
+    #[test]
+    fn interned_keys_share_storage() {
+        let a = Key::from("status");
+        let b = Key::from("status".to_string());
+        assert!(Rc::ptr_eq(&a.key, &b.key));
+        assert_eq!(a, b);
+
+        let c = Key::from("other");
+        assert!(!Rc::ptr_eq(&a.key, &c.key));
+        assert_ne!(a, c);
+        // Numeric keys are not interned and never equal string keys
+        assert_ne!(Key::from("1"), Key::from(1_u8));
+    }
+
+    #[cfg(feature = "intern_keys")]
+    #[test]
+    fn unused_interned_keys_are_freed() {
+        let key = Key::from("transient");
+        let storage = Rc::downgrade(&key.key);
+        drop(key);
+        assert!(storage.upgrade().is_none());
+
+        let kept = Key::from("kept");
+        for i in 0..1000 {
+            Key::from(format!("transient {}", i).as_str());
+        }
+        // The entries of freed strings are pruned on insert
+        assert!(super::feat_intern::len() < 200);
+        assert!(Rc::ptr_eq(&kept.key, &Key::from("kept").key));
+    }
 }
@@ -6,7 +6,7 @@ use std::{
     sync::atomic::{AtomicBool, Ordering},
 };
 use wasm_bindgen::{prelude::*, JsCast};
-use web_sys::{Element, Event};
+use web_sys::{Element, Event, EventTarget, ShadowRoot};
 
 thread_local! {
     /// Global event listener registry
@@ -346,67 +346,94 @@ struct GlobalHandlers {
     /// Events with registered handlers that are possibly passive
     handling: HashSet<EventDescriptor>,
 
+    /// Shadow roots rendered into by portals. Events that are not composed never leave the
+    /// shadow tree, so those are handled on the shadow root itself.
+    shadow_roots: Vec<ShadowRoot>,
+
     /// Keep track of all listeners to drop them on registry drop.
     /// The registry is never dropped in production.
     #[cfg(test)]
     #[allow(clippy::type_complexity)]
-    registered: Vec<(ListenerKind, Closure<dyn Fn(web_sys::Event)>)>,
+    registered: Vec<(EventTarget, ListenerKind, Closure<dyn Fn(web_sys::Event)>)>,
 }
 
 impl GlobalHandlers {
     /// Ensure a descriptor has a global event handler assigned
     fn ensure_handled(&mut self, desc: EventDescriptor) {
         if !self.handling.contains(&desc) {
-            let cl = BODY.with(|body| {
-                let cl = Closure::wrap(Box::new({
-                    let desc = desc.clone();
-                    move |e: Event| Registry::handle(desc.clone(), e)
-                }) as Box<dyn Fn(Event)>);
-                AsRef::<web_sys::EventTarget>::as_ref(body)
-                    .add_event_listener_with_callback_and_add_event_listener_options(
-                        desc.kind.type_name(),
-                        cl.as_ref().unchecked_ref(),
-                        &{
-                            let mut opts = web_sys::AddEventListenerOptions::new();
-                            opts.capture(true);
-                            // We need to explicitly set passive to override any browser defaults
-                            opts.passive(desc.passive);
-                            opts
-                        },
-                    )
-                    .map_err(|e| format!("could not register global listener: {:?}", e))
-                    .unwrap();
-                cl
-            });
-
-            // Never drop the closure as this event handler is static
-            #[cfg(not(test))]
-            cl.forget();
-            #[cfg(test)]
-            self.registered.push((desc.kind.clone(), cl));
+            BODY.with(|body| self.listen(body, &desc, false));
+            for root in self.shadow_roots.clone() {
+                self.listen(&root, &desc, true);
+            }
 
             self.handling.insert(desc);
         }
     }
+
+    /// Ensure the handled events are also handled inside the shadow tree of `root`
+    fn add_shadow_root(&mut self, root: &ShadowRoot) {
+        if !self.shadow_roots.contains(root) {
+            for desc in self.handling.clone() {
+                self.listen(root, &desc, true);
+            }
+
+            self.shadow_roots.push(root.clone());
+        }
+    }
+
+    /// Add a global event handler for a descriptor to `target`
+    fn listen(&mut self, target: &EventTarget, desc: &EventDescriptor, in_shadow_root: bool) {
+        let cl = Closure::wrap(Box::new({
+            let desc = desc.clone();
+            move |e: Event| {
+                // Composed events reach the body and are handled there
+                if in_shadow_root && e.composed() {
+                    return;
+                }
+                Registry::handle(desc.clone(), e)
+            }
+        }) as Box<dyn Fn(Event)>);
+        target
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                desc.kind.type_name(),
+                cl.as_ref().unchecked_ref(),
+                &{
+                    let mut opts = web_sys::AddEventListenerOptions::new();
+                    opts.capture(true);
+                    // We need to explicitly set passive to override any browser defaults
+                    opts.passive(desc.passive);
+                    opts
+                },
+            )
+            .map_err(|e| format!("could not register global listener: {:?}", e))
+            .unwrap();
+
+        // Never drop the closure as this event handler is static
+        #[cfg(not(test))]
+        cl.forget();
+        #[cfg(test)]
+        self.registered
+            .push((target.clone(), desc.kind.clone(), cl));
+    }
 }
 
 // Enable resetting between tests
 #[cfg(test)]
 impl Drop for GlobalHandlers {
     fn drop(&mut self) {
-        BODY.with(|body| {
-            for (kind, cl) in std::mem::take(&mut self.registered) {
-                AsRef::<web_sys::EventTarget>::as_ref(body)
-                    .remove_event_listener_with_callback(
-                        kind.type_name(),
-                        cl.as_ref().unchecked_ref(),
-                    )
-                    .unwrap();
-            }
-        });
+        for (target, kind, cl) in std::mem::take(&mut self.registered) {
+            target
+                .remove_event_listener_with_callback(kind.type_name(), cl.as_ref().unchecked_ref())
+                .unwrap();
+        }
     }
 }
 
+/// Handle the events of listeners rendered into the shadow tree of `root`.
+pub(crate) fn register_shadow_root(root: &ShadowRoot) {
+    Registry::with(|r| r.global.add_shadow_root(root));
+}
+
 /// Global multiplexing event handler registry
 #[derive(Default, Debug)]
 struct Registry {
@@ -476,10 +503,13 @@ impl Registry {
 
     /// Handle a global event firing
     fn handle(desc: EventDescriptor, event: Event) {
+        // Events from inside a shadow tree are retargeted to its host, so use the node the
+        // event was originally dispatched on instead.
         let target = match event
-            .target()
-            .map(|el| el.dyn_into::<web_sys::Element>().ok())
-            .flatten()
+            .composed_path()
+            .get(0)
+            .dyn_into::<web_sys::Element>()
+            .ok()
         {
             Some(el) => el,
             None => return,
@@ -674,6 +704,144 @@ mod tests {
         assert_count(&el, 2);
     }
 
+    #[test]
+    fn default_callback_prop() {
+        use crate::Properties;
+
+        #[derive(PartialEq, Properties)]
+        struct ButtonProps {
+            #[prop_or_default]
+            onclick: Callback<MouseEvent>,
+        }
+
+        struct Button;
+
+        impl Component for Button {
+            type Message = ();
+            type Properties = ButtonProps;
+
+            fn create(_: &Context<Self>) -> Self {
+                Self
+            }
+
+            fn view(&self, ctx: &Context<Self>) -> Html {
+                html! { <button onclick={ctx.props().onclick.clone()}>{ "click" }</button> }
+            }
+        }
+
+        struct Unhandled;
+
+        impl Mixin for Unhandled {
+            fn view<C>(ctx: &Context<C>, state: &State) -> Html
+            where
+                C: Component<Message = Message>,
+            {
+                let link = ctx.link().clone();
+                let onclick = Callback::from(move |_| {
+                    link.send_message(Message::Action);
+                    scheduler::start_now();
+                });
+
+                html! { <a {onclick}>{ state.action }<Button /></a> }
+            }
+        }
+
+        let (_, el) = init::<Unhandled>("button");
+        let a = get_el_by_tag("a");
+        assert_eq!(a.text_content().as_deref(), Some("0click"));
+
+        // Defaults to a noop callback, which lets the click bubble up to the parent's listener
+        el.click();
+        scheduler::start_now();
+        assert_eq!(a.text_content().as_deref(), Some("1click"));
+    }
+
+    #[test]
+    fn chained_callbacks() {
+        use std::cell::RefCell;
+
+        thread_local! {
+            static CALLS: RefCell<Vec<&'static str>> = RefCell::default();
+        }
+
+        struct Chained;
+
+        impl Mixin for Chained {
+            fn view<C>(ctx: &Context<C>, state: &State) -> Html
+            where
+                C: Component<Message = Message>,
+            {
+                let library = Callback::from(|_: MouseEvent| {
+                    CALLS.with(|c| c.borrow_mut().push("library"));
+                });
+                let link = ctx.link().clone();
+                let own = Callback::from(move |_: MouseEvent| {
+                    CALLS.with(|c| c.borrow_mut().push("own"));
+                    link.send_message(Message::Action);
+                });
+                let counted = Callback::from(|button: i16| {
+                    assert_eq!(button, 0);
+                    CALLS.with(|c| c.borrow_mut().push("button"));
+                });
+                let onclick = library
+                    .then(own)
+                    .then_with(counted, |e: &MouseEvent| e.button());
+
+                html! { <a {onclick}>{state.action}</a> }
+            }
+        }
+
+        let (_, el) = init::<Chained>("a");
+        el.click();
+        scheduler::start_now();
+
+        assert_count(&el, 1);
+        CALLS.with(|c| assert_eq!(*c.borrow(), ["library", "own", "button"]));
+    }
+
+    #[test]
+    fn side_effect_listener() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+        struct SideEffect;
+
+        impl Mixin for SideEffect {
+            fn view<C>(_ctx: &Context<C>, _state: &State) -> Html
+            where
+                C: Component<Message = Message>,
+            {
+                RENDERS.fetch_add(1, Ordering::Relaxed);
+                // A plain closure does not go through the component's message loop
+                html! {
+                    <form onsubmit={|e: web_sys::FocusEvent| e.prevent_default()}>
+                        <button>{ "submit" }</button>
+                    </form>
+                }
+            }
+        }
+
+        let (_, el) = init::<SideEffect>("form");
+        let renders = RENDERS.load(Ordering::Relaxed);
+
+        let not_cancelled = el
+            .dispatch_event(
+                &Event::new_with_event_init_dict("submit", &{
+                    let mut dict = EventInit::new();
+                    dict.bubbles(true);
+                    dict.cancelable(true);
+                    dict
+                })
+                .unwrap(),
+            )
+            .unwrap();
+        scheduler::start_now();
+
+        assert!(!not_cancelled);
+        assert_eq!(RENDERS.load(Ordering::Relaxed), renders);
+    }
+
     #[test]
     async fn non_bubbling_event() {
         struct NonBubbling;
@@ -719,6 +887,54 @@ mod tests {
         assert_count(&el, 1);
     }
 
+    #[test]
+    fn non_composed_events_in_shadow_root() {
+        struct ShadowInput;
+
+        thread_local! {
+            static SHADOW_ROOT: web_sys::ShadowRoot = {
+                let host = document().create_element("div").unwrap();
+                document().body().unwrap().append_child(&host).unwrap();
+                host.attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+                    .unwrap()
+            };
+        }
+
+        impl Mixin for ShadowInput {
+            fn view<C>(ctx: &Context<C>, state: &State) -> Html
+            where
+                C: Component<Message = Message>,
+            {
+                let link = ctx.link().clone();
+                let onchange = Callback::from(move |_| {
+                    link.send_message(Message::Action);
+                    scheduler::start_now();
+                });
+                let input = html! { <input {onchange} /> };
+                let shadow_root = SHADOW_ROOT.with(Clone::clone);
+                html! {
+                    <a>
+                        {state.action}
+                        {crate::html::create_shadow_portal(input, shadow_root)}
+                    </a>
+                }
+            }
+        }
+
+        let (_, el) = init::<ShadowInput>("a");
+
+        assert_count(&el, 0);
+
+        let input = SHADOW_ROOT.with(|root| root.query_selector("input").unwrap().unwrap());
+
+        // `change` events are not composed, so they never leave the shadow tree
+        input
+            .dispatch_event(&Event::new("change").unwrap())
+            .unwrap();
+
+        assert_count(&el, 1);
+    }
+
     #[test]
     fn bubbling() {
         struct Bubbling;
@@ -924,6 +1140,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn map_filter() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Digits;
+
+        impl Mixin for Digits {
+            fn view<C>(ctx: &Context<C>, state: &State) -> Html
+            where
+                C: Component<Message = Message>,
+            {
+                RENDERS.fetch_add(1, Ordering::Relaxed);
+                let oninput =
+                    ctx.link()
+                        .callback(Message::SetText)
+                        .map_filter(|e: web_sys::InputEvent| {
+                            let el: web_sys::HtmlInputElement = e.target_unchecked_into();
+                            let value = el.value();
+                            value.chars().all(|c| c.is_ascii_digit()).then(|| value)
+                        });
+
+                html! {
+                    <div>
+                        <input type="text" {oninput} />
+                        <p>{state.text.clone()}</p>
+                    </div>
+                }
+            }
+        }
+
+        let (_, input_el) = init::<Digits>("input");
+        let input_el = input_el.dyn_into::<web_sys::HtmlInputElement>().unwrap();
+        let p_el = get_el_by_tag("p");
+
+        for (value, expected, rerendered) in [("12", "12", true), ("12a", "12", false)] {
+            let renders = RENDERS.load(Ordering::Relaxed);
+            input_el.set_value(value);
+            input_el
+                .dispatch_event(
+                    &web_sys::InputEvent::new_with_event_init_dict(
+                        "input",
+                        web_sys::InputEventInit::new().bubbles(true),
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+            scheduler::start_now();
+
+            assert_eq!(&p_el.text_content().unwrap(), expected);
+            assert_eq!(
+                RENDERS.load(Ordering::Relaxed) - renders,
+                rerendered as usize
+            );
+        }
+    }
+
+    #[test]
+    fn contenteditable_oninput() {
+        struct Editable;
+
+        impl Mixin for Editable {
+            fn view<C>(ctx: &Context<C>, state: &State) -> Html
+            where
+                C: Component<Message = Message>,
+            {
+                let oninput =
+                    crate::html::contenteditable_oninput(ctx.link().callback(Message::SetText));
+
+                html! {
+                    <div>
+                        <span contenteditable="true" {oninput}></span>
+                        <p>{state.text.clone()}</p>
+                    </div>
+                }
+            }
+        }
+
+        let (_, span_el) = init::<Editable>("span");
+        let p_el = get_el_by_tag("p");
+
+        for text in ["foo", "foo bar"] {
+            span_el.set_inner_text(text);
+            span_el
+                .dispatch_event(
+                    &web_sys::InputEvent::new_with_event_init_dict(
+                        "input",
+                        web_sys::InputEventInit::new().bubbles(true),
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+            scheduler::start_now();
+
+            assert_eq!(&p_el.text_content().unwrap(), text);
+        }
+    }
+
     #[test]
     fn oninput() {
         test_input_listener(|| {
@@ -9,8 +9,12 @@ pub mod vcomp;
 #[doc(hidden)]
 pub mod vlist;
 #[doc(hidden)]
+pub mod vmemo;
+#[doc(hidden)]
 pub mod vnode;
 #[doc(hidden)]
+pub mod vnothing;
+#[doc(hidden)]
 pub mod vportal;
 #[doc(hidden)]
 pub mod vsuspense;
@@ -32,15 +36,22 @@ pub use self::listeners::*;
 #[doc(inline)]
 pub use self::vcomp::{VChild, VComp};
 #[doc(inline)]
-pub use self::vlist::VList;
+#[cfg(feature = "perf")]
+pub use self::vlist::set_reconcile_reporter;
+#[doc(inline)]
+pub use self::vlist::{set_keyed_diff_subscriber, DetachedChild, KeyedDiff, VList};
+#[doc(inline)]
+pub use self::vmemo::VMemo;
 #[doc(inline)]
 pub use self::vnode::VNode;
 #[doc(inline)]
+pub use self::vnothing::VNothing;
+#[doc(inline)]
 pub use self::vportal::VPortal;
 #[doc(inline)]
 pub use self::vsuspense::VSuspense;
 #[doc(inline)]
-pub use self::vtag::VTag;
+pub use self::vtag::{set_element_pooling, VTag};
 #[doc(inline)]
 pub use self::vtext::VText;
 use std::fmt::Formatter;
@@ -123,9 +134,20 @@ impl fmt::Display for AttrValue {
     }
 }
 
+impl AttrValue {
+    /// Returns `true`, if both values share the same string, so comparing them is trivial.
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AttrValue::Static(l), AttrValue::Static(r)) => std::ptr::eq(*l, *r),
+            (AttrValue::Rc(l), AttrValue::Rc(r)) => Rc::ptr_eq(l, r),
+            _ => false,
+        }
+    }
+}
+
 impl PartialEq for AttrValue {
     fn eq(&self, other: &Self) -> bool {
-        self.as_ref() == other.as_ref()
+        self.ptr_eq(other) || self.as_ref() == other.as_ref()
     }
 }
 
@@ -300,6 +322,7 @@ impl Attributes {
         A: AsRef<str>,
         B: AsRef<str>,
     {
+        let mut batch = AttributeBatch::default();
         let mut old_iter = old.iter();
         loop {
             match (new_iter.next(), old_iter.next()) {
@@ -308,7 +331,7 @@ impl Attributes {
                         break;
                     }
                     if new_value != old_value.as_ref() {
-                        Self::set_attribute(el, new_key, new_value);
+                        batch.update(new_key, new_value, old_value.as_ref());
                     }
                 }
                 // new attributes
@@ -317,21 +340,19 @@ impl Attributes {
                         match old.get(key) {
                             Some(old_value) => {
                                 if value != old_value.as_ref() {
-                                    Self::set_attribute(el, key, value);
+                                    batch.update(key, value, old_value.as_ref());
                                 }
                             }
-                            None => {
-                                Self::set_attribute(el, key, value);
-                            }
+                            None => batch.set(key, value),
                         }
                     }
                     break;
                 }
                 // removed attributes
                 (None, Some(attr)) => {
-                    for (key, _) in iter::once(attr).chain(old_iter) {
+                    for (key, old_value) in iter::once(attr).chain(old_iter) {
                         if !new.contains_key(key) {
-                            Self::remove_attribute(el, key);
+                            batch.remove(key, old_value.as_ref());
                         }
                     }
                     break;
@@ -339,6 +360,7 @@ impl Attributes {
                 (None, None) => break,
             }
         }
+        batch.write(el);
     }
 
     /// Convert [Attributes] pair to [HashMap]s and patch changes to `el`.
@@ -361,32 +383,110 @@ impl Attributes {
 
         let new = collect(new);
         let old = collect(old);
+        let mut batch = AttributeBatch::default();
 
         // Update existing or set new
         for (k, new) in new.iter() {
-            if match old.get(k) {
-                Some(old) => old != new,
-                None => true,
-            } {
-                el.set_attribute(k, new).unwrap();
+            match old.get(k) {
+                Some(old) if old != new => batch.update(k, new, old),
+                Some(_) => (),
+                None => batch.set(k, new),
             }
         }
 
         // Remove missing
-        for k in old.keys() {
+        for (k, old) in old.iter() {
             if !new.contains_key(k) {
-                Self::remove_attribute(el, k);
+                batch.remove(k, old);
             }
         }
+        batch.write(el);
     }
 
     fn set_attribute(el: &Element, key: &str, value: &str) {
         el.set_attribute(key, value).expect("invalid attribute key")
     }
 
-    fn remove_attribute(el: &Element, key: &str) {
-        el.remove_attribute(key)
-            .expect("could not remove attribute")
+    /// Patch the class list token by token, so classes added to the element outside of Yew,
+    /// e.g. by animation libraries, are kept.
+    fn update_class(el: &Element, new: &str, old: &str) {
+        let class_list = el.class_list();
+        for token in old.split_ascii_whitespace() {
+            if !new.split_ascii_whitespace().any(|t| t == token) {
+                class_list.remove_1(token).expect("invalid class name");
+            }
+        }
+        for token in new.split_ascii_whitespace() {
+            if !old.split_ascii_whitespace().any(|t| t == token) {
+                class_list.add_1(token).expect("invalid class name");
+            }
+        }
+    }
+}
+
+/// Attribute changes collected while diffing [Attributes], which are written to the element at
+/// once.
+///
+/// Interleaving reads from the element with the writes could force the browser to recalculate
+/// styles repeatedly. All changes are written first and the element is only read afterwards.
+#[derive(Default)]
+struct AttributeBatch<'a> {
+    /// Attributes to set to a new value
+    set: Vec<(&'a str, &'a str)>,
+
+    /// Attributes to remove
+    remove: Vec<&'a str>,
+
+    /// New and old value of the `class` attribute, which is patched token by token
+    class: Option<(&'a str, &'a str)>,
+
+    /// Remove the `class` attribute, unless classes were added to the element outside of Yew
+    remove_class: bool,
+}
+
+impl<'a> AttributeBatch<'a> {
+    /// Set a previously missing attribute
+    fn set(&mut self, key: &'a str, value: &'a str) {
+        self.set.push((key, value));
+    }
+
+    /// Change the value of an attribute previously set to `old`
+    fn update(&mut self, key: &'a str, new: &'a str, old: &'a str) {
+        if key == "class" {
+            self.class = Some((new, old));
+        } else {
+            self.set.push((key, new));
+        }
+    }
+
+    /// Remove an attribute previously set to `old`
+    fn remove(&mut self, key: &'a str, old: &'a str) {
+        if key == "class" {
+            self.class = Some(("", old));
+            self.remove_class = true;
+        } else {
+            self.remove.push(key);
+        }
+    }
+
+    /// Write all collected changes to `el`
+    fn write(self, el: &Element) {
+        for (key, value) in self.set {
+            Attributes::set_attribute(el, key, value);
+        }
+        for key in self.remove {
+            el.remove_attribute(key)
+                .expect("could not remove attribute");
+        }
+        if let Some((new, old)) = self.class {
+            Attributes::update_class(el, new, old);
+        }
+
+        // The only read happens after all changes were written
+        if self.remove_class && el.class_list().length() == 0 {
+            el.remove_attribute("class")
+                .expect("could not remove attribute");
+        }
     }
 }
 
@@ -438,31 +538,26 @@ impl Apply for Attributes {
                 // Double zipping does not optimize well, so use asserts and unsafe instead
                 assert!(new_k.len() == new_v.len());
                 assert!(new_k.len() == old_v.len());
+                let mut batch = AttributeBatch::default();
                 for i in 0..new_k.len() {
                     macro_rules! key {
                         () => {
                             unsafe { new_k.get_unchecked(i) }
                         };
                     }
-                    macro_rules! set {
-                        ($new:expr) => {
-                            Self::set_attribute(el, key!(), $new)
-                        };
-                    }
 
                     match unsafe { (new_v.get_unchecked(i), old_v.get_unchecked(i)) } {
                         (Some(new), Some(old)) => {
                             if new != old {
-                                set!(new);
+                                batch.update(key!(), new, old);
                             }
                         }
-                        (Some(new), None) => set!(new),
-                        (None, Some(_)) => {
-                            Self::remove_attribute(el, key!());
-                        }
+                        (Some(new), None) => batch.set(key!(), new),
+                        (None, Some(old)) => batch.remove(key!(), old),
                         (None, None) => (),
                     }
                 }
+                batch.write(el);
             }
             // For VTag's constructed outside the html! macro
             (Self::IndexMap(new), Self::IndexMap(old)) => {
@@ -499,13 +594,13 @@ pub(crate) trait VDiff {
     /// Remove self from parent.
     ///
     /// Parent to detach is `true` if the parent element will also be detached.
-    fn detach(&mut self, parent: &Element, parent_to_detach: bool);
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool);
 
     /// Move elements from one parent to another parent.
     /// This is currently only used by `VSuspense` to preserve component state without detaching
     /// (which destroys component state).
     /// Prefer `detach` then apply if possible.
-    fn shift(&self, previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef);
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef);
 
     /// Scoped diff apply to other tree.
     ///
@@ -535,13 +630,46 @@ pub(crate) trait VDiff {
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef;
 }
 
-pub(crate) fn insert_node(node: &Node, parent: &Element, next_sibling: Option<&Node>) {
+/// Options for rendering virtual DOM nodes to a string
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RenderOptions {
+    /// Whether to include the information needed to hydrate the markup
+    pub hydratable: bool,
+    /// Whether to put the children of elements on their own indented lines
+    pub pretty: bool,
+    /// Number of elements the rendered nodes are nested in, which they are indented by
+    pub depth: usize,
+    /// Whether to fail rendering on duplicate keys in a keyed list instead of rendering them
+    pub reject_duplicate_keys: bool,
+}
+
+#[cfg(feature = "ssr")]
+impl RenderOptions {
+    /// Starts a new line, indented to the current depth.
+    pub fn write_line_break(&self, w: &mut String) {
+        w.push('\n');
+        for _ in 0..self.depth {
+            w.push_str("  ");
+        }
+    }
+}
+
+/// Writes ` key="..."` for debug strings, if there is a key
+pub(crate) fn write_debug_key(w: &mut String, key: Option<&Key>) {
+    if let Some(key) = key {
+        w.push_str(" key=");
+        w.push_str(&format!("{:?}", key.as_str()));
+    }
+}
+
+pub(crate) fn insert_node(node: &Node, parent: &Node, next_sibling: Option<&Node>) {
     match next_sibling {
         Some(next_sibling) => parent
             .insert_before(node, Some(next_sibling))
@@ -550,6 +678,16 @@ pub(crate) fn insert_node(node: &Node, parent: &Element, next_sibling: Option<&N
     };
 }
 
+/// Removes `node` from `parent`, if it is still a child of `parent`.
+///
+/// Returns `false` if `node` was already removed, e.g. by an overlapping update.
+pub(crate) fn remove_node(node: &Node, parent: &Node) -> bool {
+    if node.parent_node().as_ref() != Some(parent) {
+        return false;
+    }
+    parent.remove_child(node).is_ok()
+}
+
 #[cfg(all(test, feature = "wasm_bench"))]
 mod benchmarks {
     use super::*;
@@ -1,7 +1,13 @@
 //! This module contains the implementation of a virtual component (`VComp`).
 
+#[cfg(feature = "ssr")]
+use super::RenderOptions;
 use super::{Key, VDiff, VNode};
-use crate::html::{AnyScope, BaseComponent, NodeRef, Scope, Scoped};
+#[cfg(feature = "ssr")]
+use crate::html::RenderResult;
+use crate::html::{
+    AnyScope, BaseComponent, ComponentRef, IntoComponentRef, NodeRef, Scope, Scoped,
+};
 #[cfg(feature = "ssr")]
 use futures::future::{FutureExt, LocalBoxFuture};
 use std::any::TypeId;
@@ -11,7 +17,7 @@ use std::ops::Deref;
 use std::rc::Rc;
 #[cfg(debug_assertions)]
 use std::sync::atomic::{AtomicUsize, Ordering};
-use web_sys::Element;
+use web_sys::Node;
 
 #[cfg(debug_assertions)]
 thread_local! {
@@ -82,6 +88,8 @@ pub struct VChild<COMP: BaseComponent> {
     pub props: Rc<COMP::Properties>,
     /// Reference to the mounted node
     node_ref: NodeRef,
+    /// Reference to the mounted component
+    comp_ref: Option<ComponentRef<COMP>>,
     key: Option<Key>,
 }
 
@@ -90,6 +98,7 @@ impl<COMP: BaseComponent> Clone for VChild<COMP> {
         VChild {
             props: Rc::clone(&self.props),
             node_ref: self.node_ref.clone(),
+            comp_ref: self.comp_ref.clone(),
             key: self.key.clone(),
         }
     }
@@ -109,10 +118,19 @@ where
     COMP: BaseComponent,
 {
     /// Creates a child component that can be accessed and modified by its parent.
-    pub fn new(props: COMP::Properties, node_ref: NodeRef, key: Option<Key>) -> Self {
+    ///
+    /// `node_ref` is either a [NodeRef] to the first node of the component or a [ComponentRef]
+    /// to the component itself.
+    pub fn new(
+        props: COMP::Properties,
+        node_ref: impl IntoComponentRef<COMP>,
+        key: Option<Key>,
+    ) -> Self {
+        let (node_ref, comp_ref) = node_ref.into_refs();
         Self {
             props: Rc::new(props),
             node_ref,
+            comp_ref,
             key,
         }
     }
@@ -123,20 +141,34 @@ where
     COMP: BaseComponent,
 {
     fn from(vchild: VChild<COMP>) -> Self {
-        VComp::new::<COMP>(vchild.props, vchild.node_ref, vchild.key)
+        let wrapper = PropsWrapper::<COMP> {
+            props: vchild.props,
+            comp_ref: vchild.comp_ref,
+        };
+        VComp::from_wrapper(wrapper, vchild.node_ref, vchild.key)
     }
 }
 
 impl VComp {
     /// Creates a new `VComp` instance.
+    ///
+    /// Without a `key`, the component's own [`key`](BaseComponent::key) is used.
     pub fn new<COMP>(props: Rc<COMP::Properties>, node_ref: NodeRef, key: Option<Key>) -> Self
     where
         COMP: BaseComponent,
     {
+        Self::from_wrapper(PropsWrapper::<COMP>::new(props), node_ref, key)
+    }
+
+    fn from_wrapper<COMP>(wrapper: PropsWrapper<COMP>, node_ref: NodeRef, key: Option<Key>) -> Self
+    where
+        COMP: BaseComponent,
+    {
+        let key = key.or_else(|| COMP::key(&wrapper.props));
         VComp {
             type_id: TypeId::of::<COMP>(),
             node_ref,
-            mountable: Some(Box::new(PropsWrapper::<COMP>::new(props))),
+            mountable: Some(Box::new(wrapper)),
             scope: None,
             key,
 
@@ -149,6 +181,20 @@ impl VComp {
         self.scope.as_ref().and_then(|scope| scope.root_vnode())
     }
 
+    /// Writes the component as a self-closing tag named after its type, see
+    /// [VList::to_debug_string](super::VList::to_debug_string)
+    pub(crate) fn write_debug_string(&self, w: &mut String) {
+        let type_name = match (&self.scope, &self.mountable) {
+            (Some(scope), _) => scope.type_name(),
+            (None, Some(mountable)) => mountable.type_name(),
+            (None, None) => "_",
+        };
+        w.push('<');
+        w.push_str(type_name);
+        super::write_debug_key(w, self.key.as_ref());
+        w.push_str(" />");
+    }
+
     /// Take ownership of [Box<dyn Scoped>] or panic with error message, if component is not mounted
     #[inline]
     fn take_scope(&mut self) -> Box<dyn Scoped> {
@@ -176,26 +222,32 @@ trait Mountable {
         self: Box<Self>,
         node_ref: NodeRef,
         parent_scope: &AnyScope,
-        parent: Element,
+        parent: Node,
         next_sibling: NodeRef,
     ) -> Box<dyn Scoped>;
     fn reuse(self: Box<Self>, node_ref: NodeRef, scope: &dyn Scoped, next_sibling: NodeRef);
+    fn type_name(&self) -> &'static str;
 
     #[cfg(feature = "ssr")]
     fn render_to_string<'a>(
         &'a self,
         w: &'a mut String,
         parent_scope: &'a AnyScope,
-    ) -> LocalBoxFuture<'a, ()>;
+        options: RenderOptions,
+    ) -> LocalBoxFuture<'a, RenderResult<()>>;
 }
 
 struct PropsWrapper<COMP: BaseComponent> {
     props: Rc<COMP::Properties>,
+    comp_ref: Option<ComponentRef<COMP>>,
 }
 
 impl<COMP: BaseComponent> PropsWrapper<COMP> {
     fn new(props: Rc<COMP::Properties>) -> Self {
-        Self { props }
+        Self {
+            props,
+            comp_ref: None,
+        }
     }
 }
 
@@ -203,6 +255,7 @@ impl<COMP: BaseComponent> Mountable for PropsWrapper<COMP> {
     fn copy(&self) -> Box<dyn Mountable> {
         let wrapper: PropsWrapper<COMP> = PropsWrapper {
             props: Rc::clone(&self.props),
+            comp_ref: self.comp_ref.clone(),
         };
         Box::new(wrapper)
     }
@@ -211,10 +264,13 @@ impl<COMP: BaseComponent> Mountable for PropsWrapper<COMP> {
         self: Box<Self>,
         node_ref: NodeRef,
         parent_scope: &AnyScope,
-        parent: Element,
+        parent: Node,
         next_sibling: NodeRef,
     ) -> Box<dyn Scoped> {
         let scope: Scope<COMP> = Scope::new(Some(parent_scope.clone()));
+        if let Some(comp_ref) = &self.comp_ref {
+            comp_ref.set(scope.clone());
+        }
         scope.mount_in_place(parent, next_sibling, node_ref, self.props);
 
         Box::new(scope)
@@ -222,29 +278,37 @@ impl<COMP: BaseComponent> Mountable for PropsWrapper<COMP> {
 
     fn reuse(self: Box<Self>, node_ref: NodeRef, scope: &dyn Scoped, next_sibling: NodeRef) {
         let scope: Scope<COMP> = scope.to_any().downcast();
+        if let Some(comp_ref) = &self.comp_ref {
+            comp_ref.set(scope.clone());
+        }
         scope.reuse(self.props, node_ref, next_sibling);
     }
 
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<COMP>()
+    }
+
     #[cfg(feature = "ssr")]
     fn render_to_string<'a>(
         &'a self,
         w: &'a mut String,
         parent_scope: &'a AnyScope,
-    ) -> LocalBoxFuture<'a, ()> {
+        options: RenderOptions,
+    ) -> LocalBoxFuture<'a, RenderResult<()>> {
         async move {
             let scope: Scope<COMP> = Scope::new(Some(parent_scope.clone()));
-            scope.render_to_string(w, self.props.clone()).await;
+            scope.render_to_string(w, self.props.clone(), options).await
         }
         .boxed_local()
     }
 }
 
 impl VDiff for VComp {
-    fn detach(&mut self, _parent: &Element, parent_to_detach: bool) {
+    fn detach(&mut self, _parent: &Node, parent_to_detach: bool) {
         self.take_scope().destroy(parent_to_detach);
     }
 
-    fn shift(&self, _previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef) {
+    fn shift(&self, _previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
         let scope = self.scope.as_ref().unwrap();
         scope.shift_node(next_parent.clone(), next_sibling);
     }
@@ -252,7 +316,7 @@ impl VDiff for VComp {
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
@@ -310,13 +374,18 @@ mod feat_ssr {
     use super::*;
 
     impl VComp {
-        pub(crate) async fn render_to_string(&self, w: &mut String, parent_scope: &AnyScope) {
+        pub(crate) async fn render_to_string(
+            &self,
+            w: &mut String,
+            parent_scope: &AnyScope,
+            options: RenderOptions,
+        ) -> RenderResult<()> {
             self.mountable
                 .as_ref()
                 .map(|m| m.copy())
                 .unwrap()
-                .render_to_string(w, parent_scope)
-                .await;
+                .render_to_string(w, parent_scope, options)
+                .await
         }
     }
 }
@@ -385,6 +454,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn props_storage_shared_on_noop_update() {
+        let document = gloo_utils::document();
+        let parent_scope: AnyScope = crate::html::Scope::<Comp>::new(None).into();
+        let parent_element = document.create_element("div").unwrap();
+
+        let props = Rc::new(Props {
+            field_1: 1,
+            field_2: 2,
+        });
+        let mut ancestor: VNode =
+            VComp::new::<Comp>(Rc::clone(&props), NodeRef::default(), None).into();
+        ancestor.apply(&parent_scope, &parent_element, NodeRef::default(), None);
+        scheduler::start_now();
+        assert_eq!(Rc::strong_count(&props), 2);
+
+        // Equal props in a different allocation
+        let equal_props = Rc::new(Props {
+            field_1: 1,
+            field_2: 2,
+        });
+        let mut node: VNode =
+            VComp::new::<Comp>(Rc::clone(&equal_props), NodeRef::default(), None).into();
+        node.apply(
+            &parent_scope,
+            &parent_element,
+            NodeRef::default(),
+            Some(ancestor),
+        );
+        scheduler::start_now();
+
+        assert_eq!(
+            Rc::strong_count(&props),
+            2,
+            "component should keep the original props"
+        );
+        assert_eq!(Rc::strong_count(&equal_props), 1);
+    }
+
     #[test]
     fn set_properties_to_component() {
         html! {
@@ -518,7 +626,8 @@ mod tests {
         }
     }
 
-    use super::{AnyScope, Element};
+    use super::AnyScope;
+    use web_sys::Element;
 
     fn setup_parent() -> (AnyScope, Element) {
         let scope = AnyScope::test();
@@ -601,6 +710,122 @@ mod tests {
         scheduler::start_now();
         assert!(node_ref.get().is_none());
     }
+
+    #[test]
+    fn key_change_remounts() {
+        use std::cell::Cell;
+
+        #[derive(PartialEq, Properties)]
+        struct CounterProps {
+            tick: u32,
+        }
+
+        /// Counts how many times it was rendered since it was created
+        struct Counter {
+            renders: Cell<u32>,
+        }
+
+        impl Component for Counter {
+            type Message = ();
+            type Properties = CounterProps;
+
+            fn create(_: &Context<Self>) -> Self {
+                Self {
+                    renders: Cell::new(0),
+                }
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                self.renders.set(self.renders.get() + 1);
+                html! { <span>{ self.renders.get() }</span> }
+            }
+        }
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut ancestor = html! { <Counter key="a" tick={0} /> };
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<span>1</span>");
+
+        // Same key: the component is updated and keeps its state
+        let mut node = html! { <Counter key="a" tick={1} /> };
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<span>2</span>");
+
+        // Changed key: the component is destroyed and created anew
+        ancestor = node;
+        let mut node = html! { <Counter key="b" tick={2} /> };
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<span>1</span>");
+
+        ancestor = node;
+        let mut node = html! { <Counter key="b" tick={3} /> };
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<span>2</span>");
+    }
+
+    #[test]
+    fn key_from_props() {
+        use std::cell::Cell;
+
+        #[derive(PartialEq, Properties)]
+        struct ItemProps {
+            id: u32,
+        }
+
+        /// Renders its id and how many times it was rendered since it was created
+        struct Item {
+            renders: Cell<u32>,
+        }
+
+        impl Component for Item {
+            type Message = ();
+            type Properties = ItemProps;
+
+            fn key(props: &Self::Properties) -> Option<Key> {
+                Some(props.id.into())
+            }
+
+            fn create(_: &Context<Self>) -> Self {
+                Self {
+                    renders: Cell::new(0),
+                }
+            }
+
+            fn view(&self, ctx: &Context<Self>) -> Html {
+                self.renders.set(self.renders.get() + 1);
+                html! { <i>{ format!("{}:{}", ctx.props().id, self.renders.get()) }</i> }
+            }
+        }
+
+        let items =
+            |ids: &[u32]| html! { <>{ for ids.iter().map(|&id| html! { <Item id={id} /> }) }</> };
+
+        assert_eq!(html! { <Item id={1} /> }.key(), Some(Key::from(1_u32)));
+        assert_eq!(
+            html! { <Item key="explicit" id={1} /> }.key(),
+            Some(Key::from("explicit"))
+        );
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut ancestor = items(&[1, 2, 3]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<i>1:1</i><i>2:1</i><i>3:1</i>");
+
+        // Reordered components are moved along with their state instead of being updated in place
+        let mut node = items(&[3, 1, 2]);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<i>3:2</i><i>1:2</i><i>2:2</i>");
+    }
 }
 
 #[cfg(test)]
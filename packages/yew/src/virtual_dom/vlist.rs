@@ -1,12 +1,30 @@
 //! This module contains fragments implementation.
 use super::{Key, VDiff, VNode, VText};
 use crate::html::{AnyScope, NodeRef};
-use std::collections::HashMap;
+use crate::Callback;
+#[cfg(not(debug_assertions))]
+use gloo::console;
+use gloo_utils::document;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::ops::{Deref, DerefMut};
-use web_sys::Element;
+use std::panic::Location;
+use std::rc::Rc;
+use web_sys::{Element, Node};
+
+/// Predicate deciding whether a child is unchanged, see [VList::set_unchanged]
+#[derive(Clone)]
+struct Unchanged(Rc<dyn Fn(&VNode, &VNode) -> bool>);
+
+impl fmt::Debug for Unchanged {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unchanged(_)")
+    }
+}
 
 /// This struct represents a fragment of the Virtual DOM tree.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct VList {
     /// The list of child [VNode]s
     children: Vec<VNode>,
@@ -14,15 +32,81 @@ pub struct VList {
     /// All [VNode]s in the VList have keys
     fully_keyed: bool,
 
+    /// An optional node reference to the first rendered node of the fragment
+    pub node_ref: Option<NodeRef>,
+
+    /// Net change in the number of rendered DOM nodes during the last apply
+    node_count_delta: isize,
+
+    /// Emit the children of a fully keyed list sorted by key when rendering on the server.
+    ///
+    /// This makes the output of lists built in a nondeterministic order, e.g. from a `HashMap`,
+    /// stable. It has no effect on rendering in the browser.
+    pub sort_by_key_in_ssr: bool,
+
+    /// Recycle the elements of removed keyed children for added keyed children with the same tag
+    /// when diffing by key, instead of destroying and recreating them.
+    ///
+    /// The recycled element is patched like a child with an unchanged key, so any state of the
+    /// element or its descendants, like focus, scroll positions or nested components of the same
+    /// type, carries over to the added child. The element is still unmounted from the removed
+    /// child and mounted to the added one, calling their mount hooks and updating their node
+    /// refs.
+    pub recycle_tags: bool,
+
+    /// Skips patching children, which are known to be unchanged, when diffing by position
+    unchanged: Option<Unchanged>,
+
+    /// Where this fragment was created by the `html!` macro
+    #[cfg(debug_assertions)]
+    source_location: Option<&'static Location<'static>>,
+
     pub key: Option<Key>,
 }
 
+impl PartialEq for VList {
+    fn eq(&self, other: &Self) -> bool {
+        if self.key != other.key || self.children.len() != other.children.len() {
+            return false;
+        }
+
+        // Comparing the keys of fully keyed lists is cheap and rules out most reorders and
+        // replacements before falling back to the deep comparison of every child.
+        if self.fully_keyed
+            && other.fully_keyed
+            && self
+                .children
+                .iter()
+                .zip(other.children.iter())
+                .any(|(l, r)| l.key() != r.key())
+        {
+            return false;
+        }
+
+        self.children == other.children
+    }
+}
+
 impl Default for VList {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl From<Vec<VNode>> for VList {
+    /// Creates an unkeyed fragment of the given nodes.
+    fn from(children: Vec<VNode>) -> Self {
+        Self::with_children(children, None)
+    }
+}
+
+impl From<Option<VNode>> for VList {
+    /// Creates a fragment which is empty for `None`.
+    fn from(child: Option<VNode>) -> Self {
+        Self::with_children(child.into_iter().collect(), None)
+    }
+}
+
 impl Deref for VList {
     type Target = Vec<VNode>;
 
@@ -41,6 +125,87 @@ impl DerefMut for VList {
     }
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Number of times [VList::apply_keyed] collected the keys of both lists
+    static KEY_COLLECTIONS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+    /// Number of children patched over a previously rendered child
+    static PATCHES: std::cell::Cell<usize> = std::cell::Cell::new(0);
+    /// Number of runs of children removed with a single range deletion
+    static RANGE_DELETIONS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// Returns the first key which occurs more than once, along with the indices of its first two
+/// occurrences
+fn find_duplicate_key<'a>(keys: impl Iterator<Item = &'a Key>) -> Option<(&'a Key, usize, usize)> {
+    let mut seen = HashMap::with_capacity(keys.size_hint().0);
+    keys.enumerate()
+        .find_map(|(i, key)| seen.insert(key, i).map(|first| (key, first, i)))
+}
+
+thread_local! {
+    static KEYED_DIFF_SUBSCRIBER: RefCell<Option<Callback<KeyedDiff>>> = RefCell::new(None);
+}
+
+/// The keys changed by diffing a fully keyed [VList].
+///
+/// Keys are listed in the order of the list they belong to. Children whose keys stayed in place
+/// are not listed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyedDiff {
+    /// Keys of children which were added to the list
+    pub added: Vec<Key>,
+    /// Keys of children which were removed from the list
+    pub removed: Vec<Key>,
+    /// Keys of children which were moved to a different position in the DOM
+    pub moved: Vec<Key>,
+}
+
+impl KeyedDiff {
+    /// Returns an empty diff, if there is a subscriber to report it to
+    fn subscribed() -> Option<Self> {
+        KEYED_DIFF_SUBSCRIBER
+            .with(|s| s.borrow().is_some())
+            .then(Self::default)
+    }
+
+    fn report(self) {
+        let subscriber = KEYED_DIFF_SUBSCRIBER.with(|s| s.borrow().clone());
+        if let Some(subscriber) = subscriber {
+            subscriber.emit(self);
+        }
+    }
+}
+
+/// Set a callback receiving the [KeyedDiff] of every keyed list diff that changed keys, or `None`
+/// to unsubscribe.
+pub fn set_keyed_diff_subscriber(subscriber: Option<Callback<KeyedDiff>>) {
+    KEYED_DIFF_SUBSCRIBER.with(|s| *s.borrow_mut() = subscriber);
+}
+
+/// A rendered child extracted from a [VList] by [VList::detach_by_key].
+///
+/// Its DOM nodes and component state are kept in a fragment outside of the document, until it is
+/// inserted into a list with [VList::reattach]. The child is destroyed, if it is dropped instead.
+#[derive(Debug)]
+pub struct DetachedChild {
+    node: VNode,
+    fragment: Element,
+}
+
+impl DetachedChild {
+    /// Returns the key of the extracted child
+    pub fn key(&self) -> Option<Key> {
+        self.node.key()
+    }
+}
+
+impl Drop for DetachedChild {
+    fn drop(&mut self) {
+        self.node.detach(&self.fragment, true);
+    }
+}
+
 /// Log an operation during tests for debugging purposes
 /// Set RUSTFLAGS="--cfg verbose_tests" environment variable to activate.
 macro_rules! test_log {
@@ -52,7 +217,7 @@ macro_rules! test_log {
 
 struct ElementWriter<'s> {
     parent_scope: &'s AnyScope,
-    parent: &'s Element,
+    parent: &'s Node,
     next_sibling: NodeRef,
 }
 
@@ -64,11 +229,13 @@ impl<'s> ElementWriter<'s> {
 
     fn patch(self, node: &mut VNode, ancestor: VNode) -> Self {
         test_log!("patching: {:?} -> {:?}", ancestor, node);
+        #[cfg(test)]
+        PATCHES.with(|c| c.set(c.get() + 1));
         self.write(node, Some(ancestor))
     }
 
     fn write(self, node: &mut VNode, ancestor: Option<VNode>) -> Self {
-        test_log!("parent={:?}", self.parent.outer_html());
+        test_log!("parent={:?}", self.parent.node_name());
         // Advance the next sibling reference (from right to left) and log it for testing purposes
         // Set RUSTFLAGS="--cfg verbose_tests" environment variable to activate.
         #[cfg(all(test, feature = "wasm_test", verbose_tests))]
@@ -88,6 +255,13 @@ impl VList {
         Self {
             children: Vec::new(),
             key: None,
+            node_ref: None,
+            node_count_delta: 0,
+            sort_by_key_in_ssr: false,
+            recycle_tags: false,
+            unchanged: None,
+            #[cfg(debug_assertions)]
+            source_location: None,
             fully_keyed: true,
         }
     }
@@ -97,6 +271,13 @@ impl VList {
         VList {
             fully_keyed: children.iter().all(|ch| ch.has_key()),
             children,
+            node_ref: None,
+            node_count_delta: 0,
+            sort_by_key_in_ssr: false,
+            recycle_tags: false,
+            unchanged: None,
+            #[cfg(debug_assertions)]
+            source_location: None,
             key,
         }
     }
@@ -119,25 +300,301 @@ impl VList {
         }
     }
 
+    /// Returns the net change in the number of DOM nodes rendered at the top level of the
+    /// fragment caused by the last call to `apply`.
+    ///
+    /// A positive value means nodes were added, a negative value means nodes were removed and `0`
+    /// means the node count did not change. Containers doing imperative layout can use this to
+    /// skip re-measuring, when a fragment's node count stayed the same. Nodes of nested fragments
+    /// and components are counted, while the placeholder of an empty fragment is not. Components
+    /// mounted by the apply only render afterwards and count as a single node, see
+    /// [`use_node_count_delta`](crate::functional::use_node_count_delta) for the count including
+    /// their nodes.
+    pub fn node_count_delta(&self) -> isize {
+        self.node_count_delta
+    }
+
+    /// Returns the location of the `html!` macro invocation this fragment was created by.
+    ///
+    /// Source locations are only tracked in debug builds and are always `None` otherwise.
+    #[cfg(debug_assertions)]
+    pub fn source_location(&self) -> Option<&'static Location<'static>> {
+        self.source_location
+    }
+
+    /// Returns the location of the `html!` macro invocation this fragment was created by.
+    ///
+    /// Source locations are only tracked in debug builds and are always `None` otherwise.
+    #[cfg(not(debug_assertions))]
+    pub fn source_location(&self) -> Option<&'static Location<'static>> {
+        None
+    }
+
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn __macro_set_source_location(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            self.source_location = Some(Location::caller());
+        }
+    }
+
+    /// Returns the virtual structure of this fragment as a string, e.g. for debugging or
+    /// snapshotting the tree on the client.
+    ///
+    /// Fragments are written like in the `html!` macro, elements with their tag, key,
+    /// attributes and children, texts as quoted strings and components as self-closing tags
+    /// named after their type. The DOM is not accessed, so this works for lists, which were
+    /// never applied, as well. Unlike server side rendering, the output is not valid HTML.
+    ///
+    /// # Example
+    /// ```
+    /// # use yew::prelude::*;
+    /// # use yew::virtual_dom::{VList, VNode};
+    /// # fn test() {
+    /// let node = html! { <><p key="a" class="x">{ "text" }</p></> };
+    /// if let VNode::VList(list) = node {
+    ///     assert_eq!(list.to_debug_string(), r#"<><p key="a" class="x">"text"</p></>"#);
+    /// }
+    /// # }
+    /// ```
+    pub fn to_debug_string(&self) -> String {
+        let mut w = String::new();
+        self.write_debug_string(&mut w);
+        w
+    }
+
+    pub(crate) fn write_debug_string(&self, w: &mut String) {
+        w.push('<');
+        if let Some(key) = &self.key {
+            w.push_str(&format!("key={:?}", key.as_str()));
+        }
+        w.push('>');
+        for child in self.children.iter() {
+            child.write_debug_string(w);
+        }
+        w.push_str("</>");
+    }
+
+    /// Sets a predicate, which is called with each new child and the previously rendered child at
+    /// the same position when the list is diffed by position.
+    ///
+    /// If it returns `true`, patching the position is skipped entirely and the previously rendered
+    /// child is kept. This avoids recursing into subtrees, e.g. of immutable data, which are known
+    /// to be unchanged. Fully keyed lists are diffed by key and do not call the predicate.
+    /// Children containing components or portals are always patched, as they have to be told
+    /// about their new next sibling.
+    pub fn set_unchanged(&mut self, unchanged: impl Fn(&VNode, &VNode) -> bool + 'static) {
+        self.unchanged = Some(Unchanged(Rc::new(unchanged)));
+    }
+
+    /// Returns `true`, if all the children are known to have keys.
+    ///
+    /// Fully keyed lists are diffed by key, all others by position.
+    pub fn is_fully_keyed(&self) -> bool {
+        self.fully_keyed
+    }
+
     /// Recheck, if the all the children have keys.
     ///
     /// Run this, after modifying the child list that contained only keyed children prior to the
     /// mutable dereference.
     pub fn recheck_fully_keyed(&mut self) {
-        self.fully_keyed = self.children.iter().all(|ch| ch.has_key());
+        self.fully_keyed = self.is_placeholder() || self.children.iter().all(|ch| ch.has_key());
+    }
+
+    /// Returns the child with the given key, if there is one.
+    pub fn get_by_key(&self, key: &Key) -> Option<&VNode> {
+        self.children
+            .iter()
+            .find(|child| child.key().as_ref() == Some(key))
+    }
+
+    /// Returns the child with the given key mutably, if there is one.
+    ///
+    /// Like the mutable dereference, this assumes the caller might remove the key of the child.
+    /// Run [`recheck_fully_keyed`](Self::recheck_fully_keyed) if the key is left untouched.
+    pub fn get_by_key_mut(&mut self, key: &Key) -> Option<&mut VNode> {
+        let child = self
+            .children
+            .iter_mut()
+            .find(|child| child.key().as_ref() == Some(key))?;
+        self.fully_keyed = false;
+
+        Some(child)
+    }
+
+    /// Extracts the rendered child with the given key from the list.
+    ///
+    /// The DOM nodes of the child are moved out of `parent` and component state is preserved,
+    /// so the child can be inserted into another rendered list with [`reattach`](Self::reattach).
+    /// Use this on the list last applied to `parent`, before the next render.
+    pub fn detach_by_key(&mut self, key: &Key, parent: &Node) -> Option<DetachedChild> {
+        let index = self
+            .children
+            .iter()
+            .position(|child| child.key().as_ref() == Some(key))?;
+
+        if self.children.len() == 1 {
+            // Stake out the place of the list, like an empty list does when rendered
+            let text_node = document().create_text_node("");
+            super::insert_node(&text_node, parent, self.children[0].first_node().as_ref());
+            let mut placeholder = VText::new("");
+            placeholder.reference = Some(text_node);
+            self.children.push(placeholder.into());
+        }
+
+        let node = self.children.remove(index);
+        let fragment = document()
+            .create_element("div")
+            .expect("failed to create fragment");
+        node.shift(parent, &fragment, NodeRef::default());
+
+        if index == 0 {
+            if let Some(node_ref) = &self.node_ref {
+                node_ref.set(self.children[0].first_node());
+            }
+        }
+
+        Some(DetachedChild { node, fragment })
+    }
+
+    /// Inserts a child extracted by [`detach_by_key`](Self::detach_by_key) into the list at
+    /// `index`, moving its DOM nodes into `parent`.
+    ///
+    /// `next_sibling` is the node following the list and only used when inserting at the end.
+    /// Use this on the list last applied to `parent`, so the next render of a list containing
+    /// the same key reuses the child.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn reattach(
+        &mut self,
+        index: usize,
+        mut detached: DetachedChild,
+        parent: &Node,
+        next_sibling: NodeRef,
+    ) {
+        assert!(index <= self.children.len(), "reattach index out of bounds");
+
+        let next_sibling = match self.children.get(index) {
+            Some(child) => {
+                let node_ref = NodeRef::default();
+                node_ref.set(child.first_node());
+                node_ref
+            }
+            None => next_sibling,
+        };
+        let node = std::mem::take(&mut detached.node);
+        node.shift(&detached.fragment, parent, next_sibling);
+
+        if self.is_placeholder() {
+            self.children.pop().unwrap().detach(parent, false);
+            self.children.push(node);
+        } else {
+            self.children.insert(index, node);
+        }
+        self.recheck_fully_keyed();
+
+        if index == 0 {
+            if let Some(node_ref) = &self.node_ref {
+                node_ref.set(self.children[0].first_node());
+            }
+        }
+    }
+
+    /// Returns true if the list only holds the placeholder rendered for an empty list.
+    ///
+    /// The placeholder is unkeyed, but empty lists are considered fully keyed, so it is handled
+    /// separately wherever children are diffed by key.
+    fn is_placeholder(&self) -> bool {
+        matches!(self.children.as_slice(), [VNode::VText(vtext)] if vtext.text.is_empty())
+    }
+
+    /// See [VNode::content_node_count]
+    pub(crate) fn content_node_count(&self) -> usize {
+        Self::children_node_count(&self.children)
+    }
+
+    fn children_node_count(children: &[VNode]) -> usize {
+        match children {
+            [VNode::VText(vtext)] if vtext.text.is_empty() => 0,
+            children => children.iter().map(VNode::content_node_count).sum(),
+        }
+    }
+
+    /// Detaches consecutive children.
+    ///
+    /// If the run spans several children ending in an element or text, their DOM nodes are
+    /// contiguous and removed with a single range deletion instead of one removal per node.
+    /// Removed elements are not returned to the pool this way, so the nodes are removed one by
+    /// one while element pooling is enabled.
+    fn detach_run(parent: &Node, run: &mut [VNode]) {
+        /// Returns the last DOM node of children rendering exactly one node
+        fn last_node(node: &VNode) -> Option<Node> {
+            match node {
+                VNode::VTag(vtag) => vtag.reference().cloned().map(Node::from),
+                VNode::VText(vtext) => vtext.reference.clone().map(Node::from),
+                _ => None,
+            }
+        }
+
+        let bounds = match run {
+            [first, .., last] if !super::vtag::pooling_elements() => {
+                first.first_node().zip(last_node(last))
+            }
+            _ => None,
+        };
+        match bounds {
+            Some((first, last))
+                if first.parent_node().as_ref() == Some(parent)
+                    && last.parent_node().as_ref() == Some(parent) =>
+            {
+                let range = document().create_range().expect("failed to create range");
+                range
+                    .set_start_before(&first)
+                    .expect("failed to set range start");
+                range.set_end_after(&last).expect("failed to set range end");
+                // Children are detached before their nodes are removed, so e.g. unmount hooks
+                // still see them in the document
+                for r in run.iter_mut() {
+                    test_log!("removing: {:?}", r);
+                    r.detach(parent, true);
+                }
+                range.delete_contents().expect("failed to delete range");
+                #[cfg(test)]
+                RANGE_DELETIONS.with(|c| c.set(c.get() + 1));
+            }
+            _ => {
+                for r in run.iter_mut() {
+                    test_log!("removing: {:?}", r);
+                    r.detach(parent, false);
+                }
+            }
+        }
     }
 
-    /// Diff and patch unkeyed child lists
+    /// Diff and patch unkeyed child lists.
+    ///
+    /// `rights` is drained, but keeps its capacity.
     fn apply_unkeyed(
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         lefts: &mut [VNode],
-        rights: Vec<VNode>,
+        rights: &mut Vec<VNode>,
+        unchanged: Option<&Unchanged>,
     ) -> NodeRef {
+        // Remove extra nodes
+        if rights.len() > lefts.len() {
+            Self::detach_run(parent, &mut rights[lefts.len()..]);
+            rights.truncate(lefts.len());
+        }
+
         let mut diff = lefts.len() as isize - rights.len() as isize;
         let mut lefts_it = lefts.iter_mut().rev();
-        let mut rights_it = rights.into_iter().rev();
+        let rights_it = rights.drain(..).rev();
         let mut writer = ElementWriter {
             parent_scope,
             parent,
@@ -150,16 +607,18 @@ impl VList {
             writer = writer.add(l);
             diff -= 1;
         }
-        // Remove extra nodes
-        while diff < 0 {
-            let mut r = rights_it.next().unwrap();
-            test_log!("removing: {:?}", r);
-            r.detach(parent, false);
-            diff += 1;
-        }
 
         for (l, r) in lefts_it.zip(rights_it) {
-            writer = writer.patch(l, r);
+            match unchanged {
+                Some(Unchanged(unchanged)) if !r.tracks_next_sibling() && unchanged(l, &r) => {
+                    test_log!("keeping unchanged: {:?}", r);
+                    if let Some(first) = r.first_node() {
+                        writer.next_sibling = NodeRef::new(first);
+                    }
+                    *l = r;
+                }
+                _ => writer = writer.patch(l, r),
+            }
         }
 
         writer.next_sibling
@@ -168,23 +627,87 @@ impl VList {
     /// Diff and patch fully keyed child lists.
     ///
     /// Optimized for node addition or removal from either end of the list and small changes in the
-    /// middle.
+    /// middle. `rights` is drained, but keeps its capacity. With `recycle_tags`, removed
+    /// elements are patched into added elements with the same tag.
     fn apply_keyed(
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         lefts: &mut [VNode],
-        rights: Vec<VNode>,
+        rights: &mut Vec<VNode>,
+        recycle_tags: bool,
     ) -> NodeRef {
+        // Duplicate keys, e.g. of sibling fragments, would collide when matching children by key
+        // and leave the DOM nodes of one of them behind
+        macro_rules! check_duplicates {
+            ($keys:expr) => {
+                if let Some((key, first, second)) = find_duplicate_key($keys) {
+                    #[cfg(debug_assertions)]
+                    panic!(
+                        "duplicate key `{}` of the children at indices {} and {} of a keyed list",
+                        key, first, second
+                    );
+                    #[cfg(not(debug_assertions))]
+                    {
+                        console::warn!(format!(
+                            "duplicate key `{}` of the children at indices {} and {} of a keyed \
+                             list, falling back to unkeyed diffing",
+                            key, first, second
+                        ));
+                        return Self::apply_unkeyed(
+                            parent_scope,
+                            parent,
+                            next_sibling,
+                            lefts,
+                            rights,
+                            None,
+                        );
+                    }
+                }
+            };
+        }
+
+        // The common cases of a list with unchanged keys or with children only appended at the
+        // end, e.g. a feed, are handled before collecting any keys
+        if lefts.len() >= rights.len()
+            && lefts
+                .iter()
+                .zip(rights.iter())
+                .all(|(l, r)| l.key_ref() == r.key_ref())
+        {
+            // This path is also taken on the first render, so debug builds look for duplicates
+            // here as well. Release builds only find them where the keys are collected anyway.
+            #[cfg(debug_assertions)]
+            check_duplicates!(lefts.iter().filter_map(VNode::key_ref));
+
+            if lefts.len() > rights.len() {
+                if let Some(mut diff) = KeyedDiff::subscribed() {
+                    diff.added
+                        .extend(lefts[rights.len()..].iter().filter_map(VNode::key));
+                    diff.report();
+                }
+            }
+            return Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, rights, None);
+        }
+
+        // Each key is taken from its child once and only the collected keys are used from here on
         macro_rules! map_keys {
             ($src:expr) => {
                 $src.iter()
-                    .map(|v| v.key().expect("unkeyed child in fully keyed list"))
+                    .map(|v| {
+                        v.key_ref()
+                            .cloned()
+                            .expect("unkeyed child in fully keyed list")
+                    })
                     .collect::<Vec<Key>>()
             };
         }
+        #[cfg(test)]
+        KEY_COLLECTIONS.with(|c| c.set(c.get() + 1));
         let lefts_keys = map_keys!(lefts);
         let rights_keys = map_keys!(rights);
+        check_duplicates!(lefts_keys.iter());
+        check_duplicates!(rights_keys.iter());
 
         /// Find the first differing key in 2 iterators
         fn matching_len<'a, 'b>(
@@ -197,9 +720,83 @@ impl VList {
         // Find first key mismatch from the front
         let from_start = matching_len(lefts_keys.iter(), rights_keys.iter());
 
+        let mut diff = KeyedDiff::subscribed();
+
         if from_start == std::cmp::min(lefts.len(), rights.len()) {
             // No key changes
-            return Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, rights);
+            if let Some(mut diff) = diff {
+                diff.added.extend_from_slice(&lefts_keys[from_start..]);
+                diff.removed.extend_from_slice(&rights_keys[from_start..]);
+                diff.report();
+            }
+            return Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, rights, None);
+        }
+
+        /// Returns `k`, if `lefts` are `rights` rotated to the left by `k`
+        fn rotation(lefts: &[Key], rights: &[Key]) -> Option<usize> {
+            let n = rights.len();
+            if lefts.len() != n || n == 0 {
+                return None;
+            }
+            let k = rights.iter().position(|k| k == &lefts[0])?;
+            (k != 0 && lefts[..n - k] == rights[k..] && lefts[n - k..] == rights[..k]).then(|| k)
+        }
+
+        // Rotations, e.g. of a carousel, only need the smaller rotated part moved
+        if let Some(k) = rotation(&lefts_keys, &rights_keys) {
+            let moved = if k <= rights.len() - k {
+                for r in &rights[..k] {
+                    test_log!("moving to end: {:?}", r);
+                    r.move_before(parent, &next_sibling.get());
+                }
+                &rights_keys[..k]
+            } else {
+                let first = rights[0].first_node();
+                for r in &rights[k..] {
+                    test_log!("moving to start: {:?}", r);
+                    r.move_before(parent, &first);
+                }
+                &rights_keys[k..]
+            };
+            if let Some(mut diff) = diff {
+                diff.moved.extend_from_slice(moved);
+                diff.report();
+            }
+            rights.rotate_left(k);
+            return Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, rights, None);
+        }
+
+        /// Returns `true`, if `lefts` are `rights` with some keys removed and the order preserved
+        fn subsequence(lefts: &[Key], rights: &[Key]) -> bool {
+            let mut rights = rights.iter();
+            lefts.len() < rights.len() && lefts.iter().all(|l| rights.any(|r| r == l))
+        }
+
+        // Filtering only removes children, so the remaining ones are patched in place
+        if subsequence(&lefts_keys, &rights_keys) {
+            let mut kept = 0;
+            let mut removed_from = 0;
+            for i in 0..=rights.len() {
+                let keep = i < rights.len() && lefts_keys.get(kept) == Some(&rights_keys[i]);
+                if keep || i == rights.len() {
+                    // Remove the run of children since the last kept one at once
+                    Self::detach_run(parent, &mut rights[removed_from..i]);
+                    if let Some(diff) = &mut diff {
+                        diff.removed
+                            .extend_from_slice(&rights_keys[removed_from..i]);
+                    }
+                    removed_from = i + 1;
+                }
+                if keep {
+                    rights.swap(kept, i);
+                    kept += 1;
+                }
+            }
+            rights.truncate(kept);
+            if let Some(diff) = diff {
+                diff.report();
+            }
+            return Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, rights, None);
         }
 
         let mut writer = ElementWriter {
@@ -213,7 +810,6 @@ impl VList {
             rights_keys[from_start..].iter().rev(),
         );
         // We partially deconstruct the rights vector in several steps.
-        let mut rights = rights;
 
         // Diff matching children at the end
         let lefts_to = lefts_keys.len() - from_end;
@@ -227,42 +823,98 @@ impl VList {
         }
 
         // Diff mismatched children in the middle
-        let mut next_right_key: Option<&Key> = None;
-        let mut rights_diff: HashMap<&Key, (VNode, Option<&Key>)> =
+        let mut rights_diff: HashMap<&Key, (VNode, usize)> =
             HashMap::with_capacity(rights_to - from_start);
-        for (k, v) in rights_keys[from_start..rights_to]
+        for (i, (k, v)) in rights_keys[from_start..rights_to]
             .iter()
             .zip(rights.drain(from_start..)) // rights_to.. has been drained already
-            .rev()
+            .enumerate()
         {
-            let next_r_key = std::mem::replace(&mut next_right_key, Some(k));
-            rights_diff.insert(k, (v, next_r_key));
+            rights_diff.insert(k, (v, i));
+        }
+
+        // Children, whose previous positions increase along the longest run, keep their order
+        // relative to each other, so only the others have to be moved
+        let stable = longest_increasing(
+            &lefts_keys[from_start..lefts_to]
+                .iter()
+                .map(|k| rights_diff.get(k).map(|(_, i)| *i))
+                .collect::<Vec<_>>(),
+        );
+
+        // Keys of the removed elements, by tag, to be recycled for added elements
+        let mut recyclable: HashMap<String, Vec<&Key>> = HashMap::new();
+        let mut recycled: HashSet<&Key> = HashSet::new();
+        if recycle_tags {
+            let kept: HashSet<&Key> = lefts_keys[from_start..lefts_to].iter().collect();
+            for k in &rights_keys[from_start..rights_to] {
+                if let Some((VNode::VTag(r), _)) = rights_diff.get(k) {
+                    if !kept.contains(k) {
+                        recyclable.entry(r.tag().to_owned()).or_default().push(k);
+                    }
+                }
+            }
         }
-        let mut next_left_key: Option<&Key> = None;
-        for (l_key, l) in lefts_keys[from_start..lefts_to]
+
+        for ((l_key, l), stable) in lefts_keys[from_start..lefts_to]
             .iter()
             .zip(lefts[from_start..lefts_to].iter_mut())
+            .zip(stable)
             .rev()
         {
             match rights_diff.remove(l_key) {
                 // Reorder and diff any existing children
-                Some((r, next_r_key)) => {
-                    match (next_r_key, next_left_key) {
-                        // If the next sibling was already the same, we don't need to move the node
-                        (Some(r_next), Some(l_next)) if r_next == l_next => (),
-                        _ => {
-                            test_log!("moving as next: {:?}", r);
-                            r.move_before(parent, &writer.next_sibling.get());
+                Some((r, _)) => {
+                    if !stable {
+                        test_log!("moving as next: {:?}", r);
+                        r.move_before(parent, &writer.next_sibling.get());
+                        if let Some(diff) = &mut diff {
+                            diff.moved.push(l_key.clone());
                         }
                     }
                     writer = writer.patch(l, r);
                 }
-                // Add new children
                 None => {
-                    writer = writer.add(l);
+                    let r_key = match l {
+                        VNode::VTag(l) => recyclable.get_mut(l.tag()).and_then(Vec::pop),
+                        _ => None,
+                    };
+                    match r_key {
+                        // Recycle a removed element with the same tag
+                        Some(r_key) => {
+                            let (mut r, _) = rights_diff.remove(r_key).unwrap();
+                            if let VNode::VTag(r) = &mut r {
+                                r.key = Some(l_key.clone());
+                                r.unmount_for_recycling();
+                            }
+                            test_log!("recycling as next: {:?}", r);
+                            r.move_before(parent, &writer.next_sibling.get());
+                            recycled.insert(r_key);
+                            writer = writer.patch(l, r);
+                            if let VNode::VTag(l) = l {
+                                l.mount_recycled();
+                            }
+                        }
+                        // Add new children
+                        None => writer = writer.add(l),
+                    }
+                    if let Some(diff) = &mut diff {
+                        diff.added.push(l_key.clone());
+                    }
                 }
             }
-            next_left_key = Some(l_key);
+        }
+
+        if let Some(diff) = &mut diff {
+            // Collected from right to left
+            diff.added.reverse();
+            diff.moved.reverse();
+            diff.removed.extend(
+                rights_keys[from_start..rights_to]
+                    .iter()
+                    .filter(|k| rights_diff.contains_key(k) || recycled.contains(k))
+                    .cloned(),
+            );
         }
 
         // Remove any extra rights
@@ -274,46 +926,201 @@ impl VList {
         // Diff matching children at the start
         for (l, r) in lefts[..from_start]
             .iter_mut()
-            .zip(rights.into_iter()) // from_start.. has been drained already
+            .zip(rights.drain(..)) // from_start.. has been drained already
             .rev()
         {
             writer = writer.patch(l, r);
         }
 
+        if let Some(diff) = diff {
+            diff.report();
+        }
+
         writer.next_sibling
     }
 }
 
+/// Marks the longest subsequence of `positions` which increases, skipping `None`s
+fn longest_increasing(positions: &[Option<usize>]) -> Vec<bool> {
+    // Indices of the last element of the increasing subsequences found so far, by length. Each
+    // one ends at the smallest position possible for its length.
+    let mut tails: Vec<usize> = Vec::new();
+    // Index of the element before each element in the subsequence ending at it
+    let mut prev: Vec<Option<usize>> = vec![None; positions.len()];
+    for (i, position) in positions.iter().enumerate() {
+        let position = match position {
+            Some(position) => position,
+            None => continue,
+        };
+        let len = tails.partition_point(|&t| positions[t] < Some(*position));
+        if len > 0 {
+            prev[i] = Some(tails[len - 1]);
+        }
+        if len == tails.len() {
+            tails.push(i);
+        } else {
+            tails[len] = i;
+        }
+    }
+
+    let mut stable = vec![false; positions.len()];
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        stable[i] = true;
+        next = prev[i];
+    }
+    stable
+}
+
+#[cfg(feature = "perf")]
+mod feat_perf {
+    use crate::Callback;
+    use std::cell::{Cell, RefCell};
+
+    thread_local! {
+        /// Number of [VList](super::VList)s currently being applied
+        static DEPTH: Cell<usize> = Cell::new(0);
+        static REPORTER: RefCell<Option<Callback<f64>>> = RefCell::new(None);
+    }
+
+    /// Set a callback receiving the time in milliseconds spent applying each top-level
+    /// [VList](super::VList), or `None` to stop measuring.
+    ///
+    /// Time spent in nested lists is accounted to the outermost list being applied.
+    #[cfg_attr(documenting, doc(cfg(feature = "perf")))]
+    pub fn set_reconcile_reporter(reporter: Option<Callback<f64>>) {
+        REPORTER.with(|r| *r.borrow_mut() = reporter);
+    }
+
+    fn now() -> f64 {
+        gloo_utils::window()
+            .performance()
+            .expect("performance API unavailable")
+            .now()
+    }
+
+    /// Measures the time until it is dropped, if it belongs to the outermost list being applied
+    pub(super) struct ReconcileTimer {
+        start: Option<f64>,
+    }
+
+    impl ReconcileTimer {
+        pub(super) fn start() -> Self {
+            let outermost = DEPTH.with(|d| d.replace(d.get() + 1)) == 0;
+            let measure = outermost && REPORTER.with(|r| r.borrow().is_some());
+
+            Self {
+                start: measure.then(now),
+            }
+        }
+    }
+
+    impl Drop for ReconcileTimer {
+        fn drop(&mut self) {
+            DEPTH.with(|d| d.set(d.get() - 1));
+
+            if let Some(start) = self.start {
+                let elapsed = now() - start;
+                let reporter = REPORTER.with(|r| r.borrow().clone());
+                if let Some(reporter) = reporter {
+                    reporter.emit(elapsed);
+                }
+            }
+        }
+    }
+}
+#[cfg(feature = "perf")]
+pub use feat_perf::set_reconcile_reporter;
+
 #[cfg(feature = "ssr")]
 mod feat_ssr {
     use super::*;
+    use crate::html::{RenderError, RenderResult};
+    use crate::virtual_dom::RenderOptions;
+    use std::collections::HashSet;
 
     impl VList {
-        pub(crate) async fn render_to_string(&self, w: &mut String, parent_scope: &AnyScope) {
+        pub(crate) async fn render_to_string(
+            &self,
+            w: &mut String,
+            parent_scope: &AnyScope,
+            options: RenderOptions,
+        ) -> RenderResult<()> {
+            // Duplicate keys only panic in debug builds in the browser, but they would render
+            // markup that doesn't match the list on the server, so they can be rejected here.
+            if options.reject_duplicate_keys && self.fully_keyed {
+                let mut keys = HashSet::with_capacity(self.children.len());
+                for key in self.children.iter().filter_map(VNode::key) {
+                    if !keys.insert(key.clone()) {
+                        return Err(RenderError::DuplicateKey(key));
+                    }
+                }
+            }
+
+            let mut children: Vec<&VNode> = self.children.iter().collect();
+            if self.sort_by_key_in_ssr && self.fully_keyed {
+                children.sort_by_key(|m| m.key());
+            }
+
             // Concurrently render all children.
-            for fragment in futures::future::join_all(self.children.iter().map(|m| async move {
+            let fragments = futures::future::join_all(children.iter().map(|m| async move {
                 let mut w = String::new();
 
-                m.render_to_string(&mut w, parent_scope).await;
+                m.render_to_string(&mut w, parent_scope, options).await?;
 
-                w
+                Ok(w)
             }))
-            .await
-            {
-                w.push_str(&fragment)
+            .await;
+
+            let start = w.len();
+            for fragment in fragments {
+                let fragment = fragment?;
+                // Adjacent text would be parsed into a single text node, so mark the boundary.
+                // Text can also end or start inside of nested fragments and components, so this
+                // looks at the rendered markup rather than at the children.
+                let after_text = ends_with_text(&w[start..]);
+                // Siblings are put on separate lines
+                if options.pretty && !fragment.is_empty() && w.len() > start {
+                    options.write_line_break(w);
+                }
+                if options.hydratable && after_text && starts_with_text(&fragment) {
+                    w.push_str("<!---->");
+                }
+                w.push_str(&fragment);
             }
+
+            Ok(())
         }
     }
+
+    /// Returns whether markup starts with text. Text is escaped, so it never contains `<`.
+    fn starts_with_text(markup: &str) -> bool {
+        markup.chars().next().map_or(false, |c| c != '<')
+    }
+
+    /// Returns whether markup ends with text. Text is escaped, so it never contains `>`.
+    fn ends_with_text(markup: &str) -> bool {
+        markup.chars().last().map_or(false, |c| c != '>')
+    }
 }
 
 impl VDiff for VList {
-    fn detach(&mut self, parent: &Element, parent_to_detach: bool) {
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool) {
+        // It could be that the ref was already reused when rendering another fragment.
+        // Only unset the ref if it still points to our first node.
+        if let Some(node_ref) = &self.node_ref {
+            let first = self.children.first().and_then(VNode::first_node);
+            if first.is_some() && node_ref.get() == first {
+                node_ref.set(None);
+            }
+        }
+
         for mut child in self.children.drain(..) {
             child.detach(parent, parent_to_detach);
         }
     }
 
-    fn shift(&self, previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef) {
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
         let mut last_node_ref = next_sibling;
 
         for node in self.children.iter().rev() {
@@ -326,28 +1133,109 @@ impl VDiff for VList {
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
-        // Here, we will try to diff the previous list elements with the new
-        // ones we want to insert. For that, we will use two lists:
-        //  - lefts: new elements to render in the DOM
-        //  - rights: previously rendered elements.
-        //
-        // The left items are known since we want to insert them
-        // (self.children). For the right ones, we will look at the ancestor,
-        // i.e. the current DOM list element that we want to replace with self.
-
-        if self.children.is_empty() {
-            // Without a placeholder the next element becomes first
-            // and corrupts the order of rendering
-            // We use empty text element to stake out a place
-            self.add_child(VText::new("").into());
-        }
+        self.apply_list(parent_scope, parent, next_sibling, ancestor)
+            .0
+    }
+}
+
+impl VList {
+    /// Renders this list over `front`, which holds the previously rendered children, and swaps
+    /// the two afterwards.
+    ///
+    /// Once done, `front` holds the rendered children and this list is left empty, but reuses the
+    /// allocation of the children buffer previously held by `front`. Filling this list and
+    /// applying it every frame, e.g. in an animation loop, thus reconciles without cloning the
+    /// previous tree or reallocating children buffers. Besides its children, all settings of
+    /// this list, like its key, move to `front`.
+    ///
+    /// Returns a reference to the first rendered node.
+    pub fn apply_double_buffered(
+        &mut self,
+        parent_scope: &AnyScope,
+        parent: &Node,
+        next_sibling: NodeRef,
+        front: &mut VList,
+    ) -> NodeRef {
+        let ancestor = VNode::VList(std::mem::take(front));
+        let (first, buffer) = self.apply_list(parent_scope, parent, next_sibling, Some(ancestor));
+        std::mem::swap(self, front);
+        self.children = buffer;
+        first
+    }
+
+    /// Like [VDiff::apply], but also returns the drained children buffer of `ancestor`, so its
+    /// allocation can be reused
+    fn apply_list(
+        &mut self,
+        parent_scope: &AnyScope,
+        parent: &Node,
+        next_sibling: NodeRef,
+        ancestor: Option<VNode>,
+    ) -> (NodeRef, Vec<VNode>) {
+        #[cfg(feature = "perf")]
+        let _timer = feat_perf::ReconcileTimer::start();
+
+        // Here, we will try to diff the previous list elements with the new
+        // ones we want to insert. For that, we will use two lists:
+        //  - lefts: new elements to render in the DOM
+        //  - rights: previously rendered elements.
+        //
+        // The left items are known since we want to insert them
+        // (self.children). For the right ones, we will look at the ancestor,
+        // i.e. the current DOM list element that we want to replace with self.
+
+        let ancestor = match ancestor {
+            // An empty list rendered over another empty list takes over its placeholder, so the
+            // DOM node and any references to it stay untouched.
+            Some(VNode::VList(mut ancestor))
+                if self.children.is_empty() && ancestor.is_placeholder() =>
+            {
+                let buffer =
+                    std::mem::replace(&mut self.children, std::mem::take(&mut ancestor.children));
+                self.node_count_delta = 0;
+
+                let first = NodeRef::default();
+                first.set(self.children[0].first_node());
+                if let Some(node_ref) = &self.node_ref {
+                    node_ref.link(first.clone());
+                }
+                return (first, buffer);
+            }
+            ancestor => ancestor,
+        };
+
+        // A list rendered empty before, which had children added since, still holds the rendered
+        // placeholder. Without an ancestor taking it over, it would be left behind in the DOM.
+        if ancestor.is_none() && self.children.len() > 1 {
+            if let VNode::VText(VText {
+                text,
+                reference: Some(_),
+            }) = &self.children[0]
+            {
+                if text.is_empty() {
+                    self.children.remove(0).detach(parent, false);
+                }
+            }
+        }
+
+        let cleared = self.children.is_empty();
+        if cleared {
+            // Without a placeholder the next element becomes first
+            // and corrupts the order of rendering
+            // We use empty text element to stake out a place.
+            // It does not count towards the keyed-ness of the list, which is empty after all.
+            self.children.push(VText::new("").into());
+        }
+
+        let replaces_placeholder =
+            !cleared && matches!(&ancestor, Some(VNode::VList(v)) if v.is_placeholder());
 
         let lefts = &mut self.children;
-        let (rights, rights_fully_keyed) = match ancestor {
+        let (mut rights, rights_fully_keyed) = match ancestor {
             // If the ancestor is also a VList, then the "right" list is the previously
             // rendered items.
             Some(VNode::VList(v)) => (v.children, v.fully_keyed),
@@ -363,15 +1251,1431 @@ impl VDiff for VList {
         };
         test_log!("lefts: {:?}", lefts);
         test_log!("rights: {:?}", rights);
+        let rights_node_count = Self::children_node_count(&rights);
 
         #[allow(clippy::let_and_return)]
-        let first = if self.fully_keyed && rights_fully_keyed {
-            Self::apply_keyed(parent_scope, parent, next_sibling, lefts, rights)
+        let first = if cleared && !rights.is_empty() {
+            // Nothing to diff against, so all previous children are removed at once
+            Self::detach_run(parent, &mut rights);
+            rights.clear();
+            lefts[0].apply(parent_scope, parent, next_sibling, None)
+        } else if replaces_placeholder {
+            // The placeholder takes the place of the new children, so it is removed up front
+            // instead of being patched into the first child while the others are added around it
+            for mut r in rights.drain(..) {
+                r.detach(parent, false);
+            }
+            Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, &mut vec![], None)
+        } else if !cleared && self.fully_keyed && rights_fully_keyed {
+            Self::apply_keyed(
+                parent_scope,
+                parent,
+                next_sibling,
+                lefts,
+                &mut rights,
+                self.recycle_tags,
+            )
         } else {
-            Self::apply_unkeyed(parent_scope, parent, next_sibling, lefts, rights)
+            Self::apply_unkeyed(
+                parent_scope,
+                parent,
+                next_sibling,
+                lefts,
+                &mut rights,
+                self.unchanged.as_ref(),
+            )
         };
         test_log!("result: {:?}", lefts);
-        first
+        self.node_count_delta = self.content_node_count() as isize - rights_node_count as isize;
+        // Parent reconcilers use the returned reference as the next sibling of the node before
+        // this list, so it has to point to the first rendered node.
+        debug_assert_eq!(
+            first.get(),
+            self.children[0].first_node(),
+            "VList::apply must return a reference to its first node"
+        );
+        if let Some(node_ref) = &self.node_ref {
+            node_ref.link(first.clone());
+        }
+        (first, rights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate self as yew;
+
+    use super::*;
+    use crate::html;
+    use crate::virtual_dom::VTag;
+    use gloo_utils::document;
+    use std::rc::Rc;
+
+    #[cfg(feature = "wasm_test")]
+    use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+    #[cfg(feature = "wasm_test")]
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn keyed_list_equality() {
+        let list = |keys: &[&str]| {
+            VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <li key={key}>{ "item" }</li> })
+                    .collect(),
+                None,
+            )
+        };
+
+        assert_eq!(list(&["a", "b", "c"]), list(&["a", "b", "c"]));
+        // The children only differ in their keys, which the deep comparison doesn't look at.
+        assert_ne!(list(&["a", "b", "c"]), list(&["a", "c", "b"]));
+        assert_ne!(list(&["a", "b", "c"]), list(&["a", "b"]));
+
+        let mut changed = list(&["a", "b"]);
+        changed.push(html! { <li key="c">{ "other" }</li> });
+        assert_ne!(list(&["a", "b", "c"]), changed);
+    }
+
+    #[test]
+    fn nested_lists_fully_keyed() {
+        let fully_keyed = |node: VNode| match node {
+            VNode::VList(list) => list.fully_keyed,
+            _ => panic!("not a list"),
+        };
+        let nested = |node: VNode| match node {
+            VNode::VList(mut list) => list.children.remove(0),
+            _ => panic!("not a list"),
+        };
+
+        // The keys of nested lists count, not the keys of their children
+        let keyed_parent = html! {
+            <>
+                <key="1"><i></i></>
+                <key="2"><e key="e"></e></>
+            </>
+        };
+        assert!(fully_keyed(keyed_parent.clone()));
+        assert!(!fully_keyed(nested(keyed_parent)));
+
+        let unkeyed_parent = html! {
+            <>
+                <key="1"><i></i></>
+                <><e key="e"></e></>
+            </>
+        };
+        assert!(!fully_keyed(unkeyed_parent.clone()));
+        assert!(!fully_keyed(nested(unkeyed_parent.clone())));
+
+        let mut unkeyed_parent = unkeyed_parent;
+        if let VNode::VList(list) = &mut unkeyed_parent {
+            list.children.remove(0);
+        }
+        assert!(fully_keyed(nested(unkeyed_parent)));
+    }
+
+    #[test]
+    fn from_children() {
+        use crate::html::Children;
+
+        let keyed = Children::new(vec![html! { <i key="1"></i> }, html! { <e key="2"></e> }]);
+        let list = VList::from(keyed);
+        assert!(list.is_fully_keyed());
+        assert_eq!(list.len(), 2);
+
+        let unkeyed = Children::new(vec![html! { <i key="1"></i> }, html! { <e></e> }]);
+        assert!(!VList::from(unkeyed).is_fully_keyed());
+    }
+
+    #[test]
+    fn apply_returns_first_node() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let end = document().create_text_node("end");
+        parent.append_child(&end).unwrap();
+        let next_sibling = NodeRef::new(end.into());
+
+        let keyed = |keys: &[usize]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <i key={key}>{ key }</i> })
+                    .collect(),
+                None,
+            ))
+        };
+        let unkeyed = |len: usize| {
+            VNode::VList(VList::with_children(
+                (0..len).map(|i| html! { <b>{ i }</b> }).collect(),
+                None,
+            ))
+        };
+
+        let mut ancestor = None;
+        for mut node in vec![
+            keyed(&[1, 2, 3]),
+            // add
+            keyed(&[0, 1, 2, 3, 4]),
+            // reorder
+            keyed(&[4, 2, 0, 3, 1]),
+            // remove
+            keyed(&[2, 3]),
+            keyed(&[]),
+            unkeyed(3),
+            unkeyed(1),
+            keyed(&[5]),
+            html! { <>{ keyed(&[6, 7]) }{ unkeyed(2) }</> },
+            html! { <>{ unkeyed(2) }{ keyed(&[7, 6]) }</> },
+        ] {
+            let first = node.apply(&scope, &parent, next_sibling.clone(), ancestor);
+            assert_eq!(first.get(), parent.first_child());
+            assert_eq!(first.get(), node.first_node());
+            ancestor = Some(node);
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "duplicate key")]
+    fn duplicate_keys_panic_on_first_render() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut node = html! {
+            <>
+                <key="a"><i></i></>
+                <key="a"><p></p></>
+            </>
+        };
+        node.apply(&scope, &parent, NodeRef::default(), None);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "duplicate key")]
+    fn duplicate_keys_panic_when_appended() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut ancestor = html! {
+            <>
+                <key="a"><i></i></>
+            </>
+        };
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+
+        let mut node = html! {
+            <>
+                <key="a"><i></i></>
+                <key="b"><e></e></>
+                <key="b"><p></p></>
+            </>
+        };
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn duplicate_sibling_keys_fall_back_to_unkeyed_diffing() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut ancestor = html! {
+            <>
+                <key="a"><i></i></>
+                <key="a"><e></e></>
+            </>
+        };
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.inner_html(), "<i></i><e></e>");
+
+        let mut duplicate = html! {
+            <>
+                <key="b"><u></u></>
+                <key="b"><p></p></>
+                <key="c"><a></a></>
+            </>
+        };
+        duplicate.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<u></u><p></p><a></a>");
+
+        let mut next = html! {
+            <>
+                <key="c"><a></a></>
+                <key="a"><i></i></>
+            </>
+        };
+        next.apply(&scope, &parent, NodeRef::default(), Some(duplicate));
+        assert_eq!(parent.inner_html(), "<a></a><i></i>");
+    }
+
+    #[test]
+    fn duplicate_keys_are_found_with_indices() {
+        let keys = |keys: &[&str]| keys.iter().map(|&k| Key::from(k)).collect::<Vec<_>>();
+
+        assert_eq!(find_duplicate_key(keys(&["a", "b", "c"]).iter()), None);
+        let duplicates = keys(&["a", "b", "c", "b", "a"]);
+        assert_eq!(
+            find_duplicate_key(duplicates.iter()),
+            Some((&Key::from("b"), 1, 3))
+        );
+        // Numeric and string keys never collide
+        assert_eq!(
+            find_duplicate_key([Key::from("1"), Key::from(1_u8)].iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn detach_tolerates_removed_nodes() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut first = html! { <><i></i><b>{ "text" }</b>{ "t" }</> };
+        first.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.inner_html(), "<i></i><b>text</b>t");
+
+        // An overlapping update already removed some of the nodes
+        parent.remove_child(&parent.first_child().unwrap()).unwrap();
+        parent.remove_child(&parent.last_child().unwrap()).unwrap();
+
+        let mut second = html! { <><u></u></> };
+        second.apply(&scope, &parent, NodeRef::default(), Some(first));
+        assert_eq!(parent.inner_html(), "<u></u>");
+
+        second.detach(&parent, false);
+        second.detach(&parent, false);
+        assert_eq!(parent.inner_html(), "");
+    }
+
+    #[test]
+    fn get_child_by_key() {
+        let mut list = VList::with_children(
+            vec![
+                html! { <li key="a">{ "a" }</li> },
+                html! { <li key="b">{ "b" }</li> },
+            ],
+            None,
+        );
+
+        let b = list.get_by_key(&Key::from("b")).unwrap();
+        assert_eq!(b, &html! { <li key="b">{ "b" }</li> });
+        assert_eq!(b.key(), Some(Key::from("b")));
+        assert!(list.get_by_key(&Key::from("c")).is_none());
+
+        *list.get_by_key_mut(&Key::from("a")).unwrap() = html! { <li key="a">{ "c" }</li> };
+        assert_eq!(
+            list.get_by_key(&Key::from("a")),
+            Some(&html! { <li key="a">{ "c" }</li> })
+        );
+        assert!(list.get_by_key_mut(&Key::from("c")).is_none());
+    }
+
+    #[test]
+    fn emptied_keyed_fragment_among_keyed_siblings() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |fragment: &[&str], reversed: bool| {
+            let fragment = html! {
+                <key="x">{ for fragment.iter().map(|&c| html! { <b key={c}>{ c }</b> }) }</>
+            };
+            let mut children = vec![
+                html! { <i key="a">{ "a" }</i> },
+                fragment,
+                html! { <i key="b">{ "b" }</i> },
+            ];
+            if reversed {
+                children.reverse();
+            }
+            VNode::VList(VList::with_children(children, None))
+        };
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+
+        let mut ancestor = list(&["c", "d"], false);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.inner_html(), "<i>a</i><b>c</b><b>d</b><i>b</i>");
+
+        let mut emptied = list(&[], true);
+        emptied.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<i>b</i><i>a</i>");
+        match &emptied {
+            VNode::VList(list) => match &list[1] {
+                VNode::VList(fragment) => assert!(fragment.is_fully_keyed()),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+
+        let mut refilled = list(&["e"], false);
+        refilled.apply(&scope, &parent, NodeRef::default(), Some(emptied));
+        assert_eq!(parent.inner_html(), "<i>a</i><b>e</b><i>b</i>");
+        set_keyed_diff_subscriber(None);
+
+        // Reordering the siblings is still diffed by key
+        let moved = diffs
+            .borrow()
+            .iter()
+            .filter(|diff| !diff.moved.is_empty())
+            .count();
+        assert_eq!(moved, 2);
+    }
+
+    #[test]
+    fn keyed_diff_subscriber() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <i key={key}>{ key }</i> })
+                    .collect(),
+                None,
+            ))
+        };
+        let keys = |keys: &[&str]| keys.iter().map(|&k| Key::from(k)).collect::<Vec<_>>();
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+
+        let mut ancestor = list(&["a", "b", "c", "d"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+
+        let mut reordered = list(&["c", "a", "e", "d"]);
+        reordered.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<i>c</i><i>a</i><i>e</i><i>d</i>");
+
+        let mut appended = list(&["c", "a", "e", "d", "f"]);
+        appended.apply(&scope, &parent, NodeRef::default(), Some(reordered));
+
+        set_keyed_diff_subscriber(None);
+        let mut unsubscribed = list(&["f"]);
+        unsubscribed.apply(&scope, &parent, NodeRef::default(), Some(appended));
+
+        assert_eq!(
+            *diffs.borrow(),
+            vec![
+                KeyedDiff {
+                    added: keys(&["e"]),
+                    removed: keys(&["b"]),
+                    moved: keys(&["c"]),
+                },
+                KeyedDiff {
+                    added: keys(&["f"]),
+                    ..KeyedDiff::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn move_keyed_child_between_lists() {
+        use crate::html::{Component, Context, Html};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Stateful {
+            id: usize,
+        }
+
+        impl Component for Stateful {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                Self {
+                    id: CREATED.fetch_add(1, Ordering::Relaxed),
+                }
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! { <span>{ self.id }</span> }
+            }
+        }
+
+        let scope = AnyScope::test();
+        let left_parent = document().create_element("div").unwrap();
+        let right_parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| match key {
+                        "stateful" => html! { <Stateful key={key} /> },
+                        _ => html! { <i key={key}>{ key }</i> },
+                    })
+                    .collect(),
+                None,
+            ))
+        };
+        let as_list = |node: &mut VNode| match node {
+            VNode::VList(list) => list,
+            _ => panic!("not a list"),
+        };
+
+        let mut left = list(&["a", "stateful"]);
+        left.apply(&scope, &left_parent, NodeRef::default(), None);
+        let mut right = list(&["b"]);
+        right.apply(&scope, &right_parent, NodeRef::default(), None);
+        crate::scheduler::start_now();
+
+        let span = left_parent.last_child().unwrap();
+        let rendered = format!("<span>{}</span>", span.text_content().unwrap());
+        assert_eq!(left_parent.inner_html(), format!("<i>a</i>{}", rendered));
+
+        let detached = as_list(&mut left)
+            .detach_by_key(&Key::from("stateful"), &left_parent)
+            .unwrap();
+        assert_eq!(detached.key(), Some(Key::from("stateful")));
+        as_list(&mut right).reattach(0, detached, &right_parent, NodeRef::default());
+        crate::scheduler::start_now();
+        assert_eq!(left_parent.inner_html(), "<i>a</i>");
+        assert_eq!(right_parent.inner_html(), format!("{}<i>b</i>", rendered));
+
+        let mut next_left = list(&["a"]);
+        next_left.apply(&scope, &left_parent, NodeRef::default(), Some(left));
+        let mut next_right = list(&["stateful", "b"]);
+        next_right.apply(&scope, &right_parent, NodeRef::default(), Some(right));
+        crate::scheduler::start_now();
+
+        assert_eq!(left_parent.inner_html(), "<i>a</i>");
+        assert_eq!(right_parent.inner_html(), format!("{}<i>b</i>", rendered));
+        assert!(right_parent
+            .first_child()
+            .unwrap()
+            .is_same_node(Some(&span)));
+    }
+
+    #[test]
+    fn clearing_keyed_list_skips_diff() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let end = document().create_text_node("end");
+        parent.append_child(&end).unwrap();
+
+        let mut ancestor = VNode::VList(VList::with_children(
+            (0..50)
+                .map(|key| html! { <li key={key}>{ key }</li> })
+                .collect(),
+            None,
+        ));
+        let next_sibling = NodeRef::new(end.into());
+        ancestor.apply(&scope, &parent, next_sibling.clone(), None);
+        assert_eq!(parent.child_nodes().length(), 51);
+
+        let patches = PATCHES.with(|c| c.get());
+        let collections = KEY_COLLECTIONS.with(|c| c.get());
+
+        let mut cleared = VNode::VList(VList::new());
+        cleared.apply(&scope, &parent, next_sibling, Some(ancestor));
+
+        assert_eq!(PATCHES.with(|c| c.get()), patches);
+        assert_eq!(KEY_COLLECTIONS.with(|c| c.get()), collections);
+        assert_eq!(parent.inner_html(), "end");
+        // The placeholder is rendered in place of the list
+        assert_eq!(parent.child_nodes().length(), 2);
+        assert_eq!(parent.first_child(), cleared.first_node());
+    }
+
+    #[test]
+    fn unchanged_keyed_list_skips_key_collection() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let list = |rev: bool| {
+            let mut keys: Vec<usize> = (0..1000).collect();
+            if rev {
+                keys.reverse();
+            }
+            VNode::VList(VList::with_children(
+                keys.into_iter()
+                    .map(|key| html! { <li key={key}>{ key }</li> })
+                    .collect(),
+                None,
+            ))
+        };
+
+        let mut ancestor = list(false);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let collections = KEY_COLLECTIONS.with(|c| c.get());
+
+        let mut unchanged = list(false);
+        unchanged.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(KEY_COLLECTIONS.with(|c| c.get()), collections);
+
+        let mut reversed = list(true);
+        reversed.apply(&scope, &parent, NodeRef::default(), Some(unchanged));
+        assert_eq!(KEY_COLLECTIONS.with(|c| c.get()), collections + 1);
+        assert_eq!(parent.first_child().unwrap().text_content().unwrap(), "999");
+    }
+
+    #[test]
+    fn keys_are_taken_once_per_diff() {
+        use crate::html::{Component, Context, Html};
+        use crate::virtual_dom::vnode::KEY_READS;
+
+        struct Item;
+
+        impl Component for Item {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                Self
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! { <i></i> }
+            }
+        }
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[u32]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <Item key={key} /> })
+                    .collect(),
+                None,
+            ))
+        };
+
+        let reads = || KEY_READS.with(|c| c.get());
+
+        let mut ancestor = list(&[1, 2, 3, 4]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+
+        // Debug builds look for duplicates among unchanged keys
+        let duplicate_check = if cfg!(debug_assertions) { 4 } else { 0 };
+
+        // The keys are compared pairwise
+        let before = reads();
+        let mut unchanged = list(&[1, 2, 3, 4]);
+        unchanged.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(reads(), before + 4 + 4 + duplicate_check);
+
+        // The first pair differs, after which the keys of both lists are collected once
+        let before = reads();
+        let mut shuffled = list(&[3, 1, 5, 4, 2]);
+        shuffled.apply(&scope, &parent, NodeRef::default(), Some(unchanged));
+        assert_eq!(reads(), before + 2 + 5 + 4);
+    }
+
+    #[test]
+    fn debug_string() {
+        use crate::html::{Component, Context, Html};
+
+        struct Item;
+
+        impl Component for Item {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_ctx: &Context<Self>) -> Self {
+                Self
+            }
+
+            fn view(&self, _ctx: &Context<Self>) -> Html {
+                html! { <i></i> }
+            }
+        }
+
+        let list = match html! {
+            <key="list">
+                <div key="a" id="first">
+                    { "text" }
+                    <input value="v" />
+                </div>
+                <Item key={2} />
+                <></>
+            </>
+        } {
+            VNode::VList(list) => list,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            list.to_debug_string(),
+            format!(
+                r#"<key="list"><div key="a" id="first">"text"<input value="v" /></div><{} key="2" /><></></>"#,
+                std::any::type_name::<Item>()
+            )
+        );
+    }
+
+    #[test]
+    fn double_buffered_applies_reuse_buffers() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let mut front = VList::new();
+        let mut back = VList::new();
+        let mut first_node = None;
+
+        for frame in 0..6 {
+            // Alternate between 3 and 2 children
+            let count = 3 - frame % 2;
+            for i in 0..count {
+                back.add_child(VText::new(format!("{}{} ", frame, i)).into());
+            }
+            let previous = front.children.as_ptr();
+
+            let node_ref =
+                back.apply_double_buffered(&scope, &parent, NodeRef::default(), &mut front);
+
+            let expected: String = (0..count).map(|i| format!("{}{} ", frame, i)).collect();
+            assert_eq!(parent.text_content().unwrap(), expected);
+            assert_eq!(front.len(), count);
+            assert_eq!(node_ref.get(), parent.first_child());
+            assert!(back.is_empty());
+            if frame > 0 {
+                // The buffer of the previous frame is handed back for the next one
+                assert_eq!(back.children.as_ptr(), previous);
+                assert!(back.children.capacity() >= 2);
+                // and the rendered nodes are patched instead of recreated
+                assert_eq!(parent.first_child(), first_node);
+            }
+            first_node = parent.first_child();
+        }
+
+        front.detach(&parent, false);
+        assert_eq!(parent.child_nodes().length(), 0);
+    }
+
+    #[test]
+    fn empty_list_without_ancestor() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        // Empty lists are fully keyed, but their placeholder is not
+        let mut list = VNode::VList(VList::new());
+        list.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.child_nodes().length(), 1);
+        assert_eq!(parent.inner_html(), "");
+
+        let mut empty = html! {};
+        empty.apply(&scope, &parent, NodeRef::default(), Some(list));
+        assert_eq!(parent.child_nodes().length(), 1);
+
+        empty.detach(&parent, false);
+        assert_eq!(parent.child_nodes().length(), 0);
+    }
+
+    #[test]
+    fn reapplying_previously_empty_list_without_ancestor() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let end = document().create_text_node("END");
+        parent.append_child(&end).unwrap();
+        let next_sibling = NodeRef::new(end.into());
+
+        let mut list = VList::new();
+        list.apply(&scope, &parent, next_sibling.clone(), None);
+        assert_eq!(parent.child_nodes().length(), 2);
+        assert!(list.is_placeholder());
+
+        list.add_child(html! { <i/> });
+        list.add_child(html! { <b/> });
+        let node_ref = list.apply(&scope, &parent, next_sibling, None);
+
+        assert_eq!(parent.inner_html(), "<i></i><b></b>END");
+        assert_eq!(parent.child_nodes().length(), 3);
+        assert_eq!(list.len(), 2);
+        assert_eq!(node_ref.get(), parent.first_child());
+
+        list.detach(&parent, false);
+        assert_eq!(parent.inner_html(), "END");
+        assert_eq!(parent.child_nodes().length(), 1);
+    }
+
+    #[test]
+    fn first_child_replaces_placeholder() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let end = document().create_text_node("END");
+        parent.append_child(&end).unwrap();
+        let next_sibling = NodeRef::new(end.into());
+
+        let mut ancestor = html! { <></> };
+        ancestor.apply(&scope, &parent, next_sibling.clone(), None);
+        assert_eq!(parent.child_nodes().length(), 2);
+        let patches = PATCHES.with(|c| c.get());
+
+        let mut node = html! { <><i/></> };
+        let node_ref = node.apply(&scope, &parent, next_sibling, Some(ancestor));
+
+        // Neither patched over nor left behind
+        assert_eq!(PATCHES.with(|c| c.get()), patches);
+        assert_eq!(parent.inner_html(), "<i></i>END");
+        assert_eq!(parent.child_nodes().length(), 2);
+        assert_eq!(node_ref.get(), parent.first_child());
+        match &node {
+            VNode::VList(vlist) => assert_eq!(vlist.node_count_delta(), 0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn unchanged_predicate_skips_patches() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        // `<b>` children hold immutable data, which is never patched
+        let list = |b: &str, i: &str| {
+            let mut list = VList::with_children(
+                vec![
+                    html! { <b>{ b.to_string() }</b> },
+                    html! { <i>{ i.to_string() }</i> },
+                    html! { <b>{ b.to_string() }</b> },
+                    html! { <i>{ i.to_string() }</i> },
+                ],
+                None,
+            );
+            list.set_unchanged(|l, r| match (l, r) {
+                (VNode::VTag(l), VNode::VTag(r)) => l.tag() == "b" && r.tag() == "b",
+                _ => false,
+            });
+            VNode::VList(list)
+        };
+
+        let mut ancestor = list("b1", "i1");
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let first = parent.first_child();
+        let patches = PATCHES.with(|c| c.get());
+
+        let mut node = list("b2", "i2");
+        let node_ref = node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+
+        assert_eq!(PATCHES.with(|c| c.get()), patches + 2);
+        assert_eq!(parent.inner_html(), "<b>b1</b><i>i2</i><b>b1</b><i>i2</i>");
+        assert_eq!(node_ref.get(), first);
+
+        // The kept children are detached along with the list
+        node.detach(&parent, false);
+        assert_eq!(parent.inner_html(), "");
+    }
+
+    #[test]
+    fn unchanged_predicate_patches_components() {
+        use crate::{scheduler, Component, Context, Html};
+
+        struct Comp;
+
+        impl Component for Comp {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_: &Context<Self>) -> Self {
+                Comp
+            }
+
+            fn view(&self, _: &Context<Self>) -> Html {
+                html! { <span></span> }
+            }
+        }
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let list = |last: VNode| {
+            let mut list = VList::with_children(vec![html! { <Comp /> }, last], None);
+            // Claims the component to be unchanged
+            list.set_unchanged(|l, r| matches!((l, r), (VNode::VComp(_), VNode::VComp(_))));
+            VNode::VList(list)
+        };
+
+        let mut ancestor = list(html! { <i></i> });
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<span></span><i></i>");
+        let patches = PATCHES.with(|c| c.get());
+
+        // The component has to be told about its new next sibling
+        let mut node = list(html! { <b></b> });
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        scheduler::start_now();
+
+        assert_eq!(PATCHES.with(|c| c.get()), patches + 2);
+        assert_eq!(parent.inner_html(), "<span></span><b></b>");
+    }
+
+    #[test]
+    fn filtering_removes_in_place() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <i key={key}>{ key }</i> })
+                    .collect(),
+                None,
+            ))
+        };
+        let keys = |keys: &[&str]| keys.iter().map(|&k| Key::from(k)).collect::<Vec<_>>();
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+
+        let mut ancestor = list(&["a", "b", "c", "d", "e"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let b = parent.child_nodes().get(1).unwrap();
+        let e = parent.child_nodes().get(4).unwrap();
+
+        let mut node = list(&["b", "c", "e"]);
+        let node_ref = node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        set_keyed_diff_subscriber(None);
+
+        assert_eq!(parent.inner_html(), "<i>b</i><i>c</i><i>e</i>");
+        assert_eq!(node_ref.get(), Some(b.clone()));
+        assert_eq!(parent.child_nodes().get(0), Some(b));
+        assert_eq!(parent.child_nodes().get(2), Some(e));
+        assert_eq!(
+            *diffs.borrow(),
+            vec![KeyedDiff {
+                removed: keys(&["a", "d"]),
+                ..KeyedDiff::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn recycling_removed_tags() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str], recycle_tags: bool| {
+            let mut list = VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <div key={key}>{ key }</div> })
+                    .collect(),
+                None,
+            );
+            list.recycle_tags = recycle_tags;
+            VNode::VList(list)
+        };
+
+        let mut ancestor = list(&["a", "b", "c"], true);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let b = parent.child_nodes().get(1).unwrap();
+
+        let mut node = list(&["a", "d", "c"], true);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<div>a</div><div>d</div><div>c</div>");
+        assert_eq!(parent.child_nodes().get(1), Some(b.clone()));
+
+        // Without opting in, the removed element is destroyed
+        let mut next = list(&["a", "e", "c"], false);
+        next.apply(&scope, &parent, NodeRef::default(), Some(node));
+        assert_eq!(parent.inner_html(), "<div>a</div><div>e</div><div>c</div>");
+        assert_ne!(parent.child_nodes().get(1), Some(b));
+    }
+
+    fn recycling_list(keys: &[&'static str], hook: impl Fn(&'static str, VTag) -> VTag) -> VNode {
+        let mut list = VList::with_children(
+            keys.iter()
+                .map(|&key| {
+                    let mut tag = VTag::new("div");
+                    tag.key = Some(key.into());
+                    VNode::from(hook(key, tag))
+                })
+                .collect(),
+            None,
+        );
+        list.recycle_tags = true;
+        VNode::VList(list)
+    }
+
+    #[test]
+    fn recycling_calls_mount_hooks() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+
+        let list = |keys: &[&'static str]| {
+            recycling_list(keys, |key, mut tag| {
+                let mounted = events.clone();
+                tag.set_on_mount(move |_| mounted.borrow_mut().push(format!("+{}", key)));
+                let unmounted = events.clone();
+                tag.set_on_unmount(move |_| unmounted.borrow_mut().push(format!("-{}", key)));
+                tag
+            })
+        };
+
+        let mut ancestor = list(&["a", "b"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let b = parent.child_nodes().get(1).unwrap();
+        events.borrow_mut().clear();
+
+        let mut node = list(&["a", "c"]);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.child_nodes().get(1), Some(b));
+        assert_eq!(*events.borrow(), ["-b", "+c"]);
+    }
+
+    #[test]
+    fn recycling_moves_node_refs() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let refs: HashMap<&str, NodeRef> = ["a", "b", "c"]
+            .iter()
+            .map(|&key| (key, NodeRef::default()))
+            .collect();
+
+        let list = |keys: &[&'static str]| {
+            recycling_list(keys, |key, mut tag| {
+                tag.node_ref = refs[key].clone();
+                tag
+            })
+        };
+
+        let mut ancestor = list(&["a", "b"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let b = parent.child_nodes().get(1).unwrap();
+        assert_eq!(refs["b"].get(), Some(b.clone()));
+
+        let mut node = list(&["a", "c"]);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(refs["b"].get(), None);
+        assert_eq!(refs["c"].get(), Some(b));
+    }
+
+    #[test]
+    fn consecutive_removals_delete_range() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <i key={key}>{ key }</i> })
+                    .collect(),
+                None,
+            ))
+        };
+        let deletions = || RANGE_DELETIONS.with(|c| c.get());
+
+        let mut ancestor = list(&["a", "b", "c", "d", "e", "f"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let c = parent.child_nodes().get(2).unwrap();
+        let before = deletions();
+
+        // A contiguous block in the middle
+        let mut node = list(&["a", "e", "f"]);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<i>a</i><i>e</i><i>f</i>");
+        assert_eq!(c.parent_node(), None);
+        assert_eq!(deletions(), before + 1);
+
+        // Single children in between kept ones are removed one by one
+        let mut node_2 = list(&["e"]);
+        node_2.apply(&scope, &parent, NodeRef::default(), Some(node));
+        assert_eq!(parent.inner_html(), "<i>e</i>");
+        assert_eq!(deletions(), before + 1);
+
+        // Unkeyed children removed from the end
+        let mut ancestor = VNode::VList(VList::with_children(
+            vec![
+                html! { <b></b> },
+                html! { "x" },
+                html! { <>{ "y" }<u></u></> },
+                html! { <p></p> },
+            ],
+            None,
+        ));
+        ancestor.apply(&scope, &parent, NodeRef::default(), Some(node_2));
+        let mut node = VNode::VList(VList::with_children(vec![html! { <b></b> }], None));
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<b></b>");
+        assert_eq!(deletions(), before + 2);
+    }
+
+    #[test]
+    fn rotations_move_once() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <i key={key}>{ key }</i> })
+                    .collect(),
+                None,
+            ))
+        };
+        let keys = |keys: &[&str]| keys.iter().map(|&k| Key::from(k)).collect::<Vec<_>>();
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+
+        let mut ancestor = list(&["a", "b", "c", "d"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let b = parent.child_nodes().get(1).unwrap();
+
+        let mut left = list(&["b", "c", "d", "a"]);
+        let node_ref = left.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.inner_html(), "<i>b</i><i>c</i><i>d</i><i>a</i>");
+        assert_eq!(node_ref.get(), Some(b.clone()));
+
+        let mut right = list(&["a", "b", "c", "d"]);
+        let node_ref = right.apply(&scope, &parent, NodeRef::default(), Some(left));
+        assert_eq!(parent.inner_html(), "<i>a</i><i>b</i><i>c</i><i>d</i>");
+        assert_eq!(node_ref.get(), parent.first_child());
+        assert_eq!(parent.child_nodes().get(1), Some(b));
+        set_keyed_diff_subscriber(None);
+
+        assert_eq!(
+            *diffs.borrow(),
+            vec![
+                KeyedDiff {
+                    moved: keys(&["a"]),
+                    ..KeyedDiff::default()
+                },
+                KeyedDiff {
+                    moved: keys(&["a"]),
+                    ..KeyedDiff::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn moving_one_child_across_list_moves_once() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |keys: &[&str]| {
+            VNode::VList(VList::with_children(
+                keys.iter()
+                    .map(|&key| html! { <i key={key}>{ key }</i> })
+                    .collect(),
+                None,
+            ))
+        };
+        let text = |keys: &[&str]| {
+            keys.iter()
+                .map(|k| format!("<i>{}</i>", k))
+                .collect::<String>()
+        };
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+
+        let steps: &[&[&str]] = &[
+            &["a", "b", "c", "d", "e", "f", "g"],
+            // Backwards
+            &["a", "f", "b", "c", "d", "e", "g"],
+            // Forwards
+            &["f", "b", "c", "d", "e", "a", "g"],
+            // With additions and removals around it
+            &["x", "b", "c", "f", "d", "a", "y"],
+        ];
+        let mut ancestor = list(steps[0]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        for step in &steps[1..] {
+            let mut node = list(step);
+            node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+            assert_eq!(parent.inner_html(), text(step));
+            ancestor = node;
+        }
+        set_keyed_diff_subscriber(None);
+
+        let moved: Vec<_> = diffs.borrow().iter().map(|d| d.moved.clone()).collect();
+        assert_eq!(
+            moved,
+            [
+                vec![Key::from("f")],
+                vec![Key::from("a")],
+                vec![Key::from("f")]
+            ]
+        );
+    }
+
+    #[test]
+    fn appending_to_keyed_list_skips_key_collection() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let list = |len: usize| {
+            VNode::VList(VList::with_children(
+                (0..len)
+                    .map(|key| html! { <li key={key}>{ key }</li> })
+                    .collect(),
+                None,
+            ))
+        };
+
+        let mut ancestor = list(1000);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+        let collections = KEY_COLLECTIONS.with(|c| c.get());
+        let patches = PATCHES.with(|c| c.get());
+
+        let mut appended = list(1001);
+        appended.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        set_keyed_diff_subscriber(None);
+
+        assert_eq!(KEY_COLLECTIONS.with(|c| c.get()), collections);
+        assert_eq!(PATCHES.with(|c| c.get()), patches + 1000);
+        assert_eq!(
+            *diffs.borrow(),
+            vec![KeyedDiff {
+                added: vec![Key::from(1000_usize)],
+                ..KeyedDiff::default()
+            }]
+        );
+        assert_eq!(parent.child_nodes().length(), 1001);
+        assert_eq!(parent.last_child().unwrap().text_content().unwrap(), "1000");
+    }
+
+    #[test]
+    fn keyed_sibling_lists_move_as_unit() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let mut first = html! {
+            <>
+                <key="1"><i></i><e></e></>
+                <key="2"><a></a><u></u></>
+            </>
+        };
+        first.apply(&scope, &parent, NodeRef::default(), None);
+        let children = parent.child_nodes();
+        let originals: Vec<_> = (0..children.length())
+            .map(|i| children.item(i).unwrap())
+            .collect();
+
+        let mut second = html! {
+            <>
+                <key="2"><a></a><u></u></>
+                <key="1"><i></i><e></e></>
+            </>
+        };
+        second.apply(&scope, &parent, NodeRef::default(), Some(first));
+        assert_eq!(parent.inner_html(), "<a></a><u></u><i></i><e></e>");
+
+        // The children of both lists were moved, not recreated
+        let children = parent.child_nodes();
+        for (i, original) in [2, 3, 0, 1].iter().map(|&i| &originals[i]).enumerate() {
+            assert_eq!(&children.item(i as u32).unwrap(), original);
+        }
+    }
+
+    #[cfg(feature = "perf")]
+    #[test]
+    fn reconcile_reporter() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let durations: Rc<RefCell<Vec<f64>>> = Rc::default();
+        set_reconcile_reporter(Some({
+            let durations = durations.clone();
+            crate::Callback::from(move |elapsed| durations.borrow_mut().push(elapsed))
+        }));
+
+        let mut list = html! {
+            <>
+                { for (0..1000).map(|i| html! { <><p>{ i }</p></> }) }
+            </>
+        };
+        list.apply(&scope, &parent, NodeRef::default(), None);
+        set_reconcile_reporter(None);
+
+        // Only the outermost list is reported
+        let durations = durations.borrow();
+        assert_eq!(durations.len(), 1);
+        assert!(durations[0] > 0.0);
+    }
+
+    #[test]
+    fn empty_list_reuses_placeholder() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let node_ref = NodeRef::default();
+        let mut first = html! { <ref={node_ref.clone()}></> };
+        first.apply(&scope, &parent, NodeRef::default(), None);
+        let placeholder = parent.first_child().unwrap();
+        assert_eq!(node_ref.get(), Some(placeholder.clone()));
+
+        let mut second = html! { <ref={node_ref.clone()}></> };
+        second.apply(&scope, &parent, NodeRef::default(), Some(first));
+        assert_eq!(parent.child_nodes().length(), 1);
+        assert_eq!(parent.first_child(), Some(placeholder.clone()));
+        assert_eq!(node_ref.get(), Some(placeholder));
+        assert_eq!(second.first_node(), parent.first_child());
+    }
+
+    #[test]
+    fn repeated_empty_renders_skip_diff() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let diffs = Rc::new(RefCell::new(Vec::new()));
+        set_keyed_diff_subscriber(Some({
+            let diffs = diffs.clone();
+            Callback::from(move |diff| diffs.borrow_mut().push(diff))
+        }));
+
+        let mut ancestor = VNode::VList(VList::new());
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let placeholder = parent.first_child().unwrap();
+        let patches = PATCHES.with(|c| c.get());
+        let collections = KEY_COLLECTIONS.with(|c| c.get());
+
+        for _ in 0..3 {
+            let mut node = VNode::VList(VList::new());
+            let node_ref = node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+            assert_eq!(node_ref.get(), Some(placeholder.clone()));
+            assert_eq!(parent.child_nodes().length(), 1);
+            match &node {
+                VNode::VList(list) => assert_eq!(list.node_count_delta(), 0),
+                _ => unreachable!(),
+            }
+            ancestor = node;
+        }
+        set_keyed_diff_subscriber(None);
+
+        assert_eq!(PATCHES.with(|c| c.get()), patches);
+        assert_eq!(KEY_COLLECTIONS.with(|c| c.get()), collections);
+        assert!(diffs.borrow().is_empty());
+    }
+
+    #[test]
+    fn fragment_node_ref() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let node_ref = NodeRef::default();
+        let mut elem = html! {
+            <ref={node_ref.clone()}>
+                <span id="first"></span>
+                <p></p>
+            </>
+        };
+        elem.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(node_ref.get(), parent.first_child());
+        assert_eq!(
+            node_ref.cast::<Element>().unwrap().outer_html(),
+            "<span id=\"first\"></span>"
+        );
+
+        elem.detach(&parent, false);
+        assert!(node_ref.get().is_none());
+    }
+
+    #[test]
+    fn string_to_numeric_key_recreates_node() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let mut before = html! {
+            <>
+                <p key="1"></p>
+                <p key="2"></p>
+            </>
+        };
+        before.apply(&scope, &parent, NodeRef::default(), None);
+        let first_before = parent.first_child().unwrap();
+        let last_before = parent.last_child().unwrap();
+
+        let mut after = html! {
+            <>
+                <p key=1></p>
+                <p key="2"></p>
+            </>
+        };
+        after.apply(&scope, &parent, NodeRef::default(), Some(before));
+        assert_eq!(parent.child_nodes().length(), 2);
+        assert_ne!(
+            parent.first_child().unwrap(),
+            first_before,
+            "node keyed with a string should not be reused for a numeric key"
+        );
+        assert_eq!(parent.last_child().unwrap(), last_before);
+    }
+
+    #[test]
+    fn node_count_delta() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        fn apply(
+            scope: &AnyScope,
+            parent: &Element,
+            mut node: VNode,
+            ancestor: Option<VNode>,
+        ) -> (VNode, isize) {
+            node.apply(scope, parent, NodeRef::default(), ancestor);
+            let delta = match &node {
+                VNode::VList(vlist) => vlist.node_count_delta(),
+                _ => panic!("expected a VList"),
+            };
+            (node, delta)
+        }
+
+        let (node, delta) = apply(&scope, &parent, html! { <><i/><e/></> }, None);
+        assert_eq!(delta, 2);
+
+        let (node, delta) = apply(&scope, &parent, html! { <><i/><e/><p/></> }, Some(node));
+        assert_eq!(delta, 1);
+
+        let (node, delta) = apply(&scope, &parent, html! { <><a/><u/><p/></> }, Some(node));
+        assert_eq!(delta, 0);
+
+        let (node, delta) = apply(&scope, &parent, html! { <><a/></> }, Some(node));
+        assert_eq!(delta, -2);
+
+        // The placeholder of a cleared list is not counted
+        let (node, delta) = apply(&scope, &parent, html! { <></> }, Some(node));
+        assert_eq!(delta, -1);
+
+        // Nodes of nested fragments are counted individually
+        let (node, delta) = apply(
+            &scope,
+            &parent,
+            html! { <><><i/><e/></><p/></> },
+            Some(node),
+        );
+        assert_eq!(delta, 3);
+
+        let (_, delta) = apply(&scope, &parent, html! { <></> }, Some(node));
+        assert_eq!(delta, -3);
     }
 }
 
@@ -459,7 +2763,7 @@ mod layout_tests_keys {
 
     use crate::html;
     use crate::tests::layout_tests::{diff_layouts, TestLayout};
-    use crate::virtual_dom::VNode;
+    use crate::virtual_dom::{Key, VNode};
     use crate::{Children, Component, Context, Html, Properties};
     use web_sys::Node;
 
@@ -500,9 +2804,28 @@ mod layout_tests_keys {
         pub children: Children,
     }
 
-    pub struct List();
+    pub struct List();
+
+    impl Component for List {
+        type Message = ();
+        type Properties = ListProps;
+
+        fn create(_: &Context<Self>) -> Self {
+            Self()
+        }
+
+        fn update(&mut self, _ctx: &Context<Self>, _: Self::Message) -> bool {
+            unimplemented!();
+        }
+
+        fn view(&self, ctx: &Context<Self>) -> Html {
+            html! { <>{ for ctx.props().children.iter() }</> }
+        }
+    }
+
+    pub struct WrappedList();
 
-    impl Component for List {
+    impl Component for WrappedList {
         type Message = ();
         type Properties = ListProps;
 
@@ -510,12 +2833,28 @@ mod layout_tests_keys {
             Self()
         }
 
-        fn update(&mut self, _ctx: &Context<Self>, _: Self::Message) -> bool {
-            unimplemented!();
+        fn view(&self, ctx: &Context<Self>) -> Html {
+            ctx.props()
+                .children
+                .map_with_key(|_, child| html! { <List><b>{ child }</b></List> })
         }
+    }
 
-        fn view(&self, ctx: &Context<Self>) -> Html {
-            html! { <>{ for ctx.props().children.iter() }</> }
+    #[test]
+    fn map_with_key_carries_keys() {
+        let children = Children::new(vec![
+            html! { <i key="i"></i> },
+            html! { <e></e> },
+            html! { <p key="p"></p> },
+        ]);
+        let wrapped = children.map_with_key(|_, child| html! { <List>{ child }</List> });
+
+        match wrapped {
+            VNode::VList(vlist) => {
+                let keys: Vec<_> = vlist.iter().map(VNode::key).collect();
+                assert_eq!(keys, vec![Some(Key::from("i")), None, Some(Key::from("p"))]);
+            }
+            _ => panic!("expected a VList"),
         }
     }
 
@@ -1081,6 +3420,118 @@ mod layout_tests_keys {
             },
         ]);
 
+        layouts.extend(vec![
+            TestLayout {
+                name: "Rotate nested keyed lists - before",
+                node: html! {
+                    <>
+                        <key="1">
+                            <i key="i"></i>
+                            <e key="e"></e>
+                        </>
+                        <key="2">
+                            <a></a>
+                        </>
+                        <key="3">
+                            <p key="p"></p>
+                            <u key="u"></u>
+                        </>
+                    </>
+                },
+                expected: "<i></i><e></e><a></a><p></p><u></u>",
+            },
+            TestLayout {
+                name: "Rotate nested keyed lists - after",
+                node: html! {
+                    <>
+                        <key="3">
+                            <u key="u"></u>
+                            <p key="p"></p>
+                        </>
+                        <key="1">
+                            <i key="i"></i>
+                            <e key="e"></e>
+                        </>
+                        <key="2">
+                            <a></a>
+                        </>
+                    </>
+                },
+                expected: "<u></u><p></p><i></i><e></e><a></a>",
+            },
+        ]);
+
+        layouts.extend(vec![
+            TestLayout {
+                name: "Keyed parent with unkeyed nested lists - before",
+                node: html! {
+                    <>
+                        <key="1">
+                            <i></i>
+                            <e></e>
+                        </>
+                        <key="2">
+                            <a></a>
+                            <p></p>
+                        </>
+                    </>
+                },
+                expected: "<i></i><e></e><a></a><p></p>",
+            },
+            TestLayout {
+                name: "Keyed parent with unkeyed nested lists - after",
+                node: html! {
+                    <>
+                        <key="2">
+                            <p></p>
+                        </>
+                        <key="1">
+                            <e></e>
+                            <i></i>
+                            <u></u>
+                        </>
+                    </>
+                },
+                expected: "<p></p><e></e><i></i><u></u>",
+            },
+        ]);
+
+        layouts.extend(vec![
+            TestLayout {
+                name: "Unkeyed parent with keyed nested lists - before",
+                node: html! {
+                    <>
+                        <>
+                            <i key="i"></i>
+                            <e key="e"></e>
+                        </>
+                        <key="2">
+                            <a key="a"></a>
+                            <p key="p"></p>
+                        </>
+                    </>
+                },
+                expected: "<i></i><e></e><a></a><p></p>",
+            },
+            TestLayout {
+                name: "Unkeyed parent with keyed nested lists - after",
+                node: html! {
+                    <>
+                        <>
+                            <e key="e"></e>
+                            <i key="i"></i>
+                        </>
+                        <key="2">
+                            <p key="p"></p>
+                            <u key="u"></u>
+                            <a key="a"></a>
+                        </>
+                    </>
+                },
+                expected: "<e></e><i></i><p></p><u></u><a></a>",
+            },
+        ]);
+
         layouts.extend(vec![
             TestLayout {
                 name: "Insert VComp front - before",
@@ -1286,15 +3737,168 @@ mod layout_tests_keys {
             },
         ]);
 
+        layouts.extend(vec![
+            TestLayout {
+                name: "String to numeric key - before",
+                node: html! {
+                    <>
+                        <i key="1"></i>
+                        <e key="2"></e>
+                    </>
+                },
+                expected: "<i></i><e></e>",
+            },
+            TestLayout {
+                name: "String to numeric key - after",
+                node: html! {
+                    <>
+                        <i key=1></i>
+                        <e key=2></e>
+                    </>
+                },
+                expected: "<i></i><e></e>",
+            },
+        ]);
+
+        layouts.extend(vec![
+            TestLayout {
+                name: "Numeric to string key with reorder - before",
+                node: html! {
+                    <>
+                        <i key=1></i>
+                        <e key=2></e>
+                        <p key=3></p>
+                    </>
+                },
+                expected: "<i></i><e></e><p></p>",
+            },
+            TestLayout {
+                name: "Numeric to string key with reorder - after",
+                node: html! {
+                    <>
+                        <p key="3"></p>
+                        <e key=2></e>
+                        <i key="1"></i>
+                    </>
+                },
+                expected: "<p></p><e></e><i></i>",
+            },
+        ]);
+
+        layouts.extend(vec![
+            TestLayout {
+                name: "Reorder composite keys - before",
+                node: html! {
+                    <>
+                        <i key={Key::from_hashable(&(1, "a"))}></i>
+                        <e key={Key::from_hashable(&(1, "b"))}></e>
+                        <p key={Key::from_hashable(&(2, "a"))}></p>
+                    </>
+                },
+                expected: "<i></i><e></e><p></p>",
+            },
+            TestLayout {
+                name: "Reorder composite keys - after",
+                node: html! {
+                    <>
+                        <p key={Key::from_hashable(&(2, "a"))}></p>
+                        <i key={Key::from_hashable(&(1, "a"))}></i>
+                        <e key={Key::from_hashable(&(1, "b"))}></e>
+                    </>
+                },
+                expected: "<p></p><i></i><e></e>",
+            },
+        ]);
+
+        layouts.extend(vec![
+            TestLayout {
+                name: "Reorder wrapped children - before",
+                node: html! {
+                    <WrappedList>
+                        <i key="i"></i>
+                        <e key="e"></e>
+                        <p key="p"></p>
+                    </WrappedList>
+                },
+                expected: "<b><i></i></b><b><e></e></b><b><p></p></b>",
+            },
+            TestLayout {
+                name: "Reorder wrapped children - after",
+                node: html! {
+                    <WrappedList>
+                        <p key="p"></p>
+                        <i key="i"></i>
+                        <e key="e"></e>
+                    </WrappedList>
+                },
+                expected: "<b><p></p></b><b><i></i></b><b><e></e></b>",
+            },
+        ]);
+
         diff_layouts(layouts);
     }
+
+    #[test]
+    fn node_refs_survive_reorders() {
+        use crate::html::{AnyScope, NodeRef};
+        use crate::virtual_dom::VDiff;
+
+        let scope = AnyScope::test();
+        let parent = gloo_utils::document().create_element("div").unwrap();
+
+        let keys = ["a", "b", "c", "d", "e", "f"];
+        let refs: Vec<NodeRef> = keys.iter().map(|_| NodeRef::default()).collect();
+        let list = |order: &[usize]| -> VNode {
+            order
+                .iter()
+                .map(|&i| {
+                    let key = keys[i];
+                    html! { <i key={key} ref={refs[i].clone()}>{ key }</i> }
+                })
+                .collect::<Html>()
+        };
+
+        let mut ancestor = list(&[0, 1, 2, 3, 4, 5]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let nodes: Vec<Node> = refs.iter().map(|r| r.get().unwrap()).collect();
+
+        for (name, order) in [
+            ("reverse", [5, 4, 3, 2, 1, 0]),
+            ("rotate left", [4, 3, 2, 1, 0, 5]),
+            ("rotate right", [5, 4, 3, 2, 1, 0]),
+            ("swap ends", [0, 4, 3, 2, 1, 5]),
+            ("shuffle middle", [0, 3, 1, 4, 2, 5]),
+            ("restore", [0, 1, 2, 3, 4, 5]),
+        ] {
+            let mut node = list(&order);
+            node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+            ancestor = node;
+
+            let expected: String = order
+                .iter()
+                .map(|&i| format!("<i>{}</i>", keys[i]))
+                .collect();
+            assert_eq!(parent.inner_html(), expected, "{}", name);
+            for (i, r) in refs.iter().enumerate() {
+                assert_eq!(
+                    r.get().as_ref(),
+                    Some(&nodes[i]),
+                    "{}: ref of {} no longer points to its element",
+                    name,
+                    keys[i]
+                );
+            }
+        }
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32"), feature = "ssr"))]
 mod ssr_tests {
     use tokio::test;
 
+    use crate::html::RenderError;
     use crate::prelude::*;
+    use crate::virtual_dom::VList;
     use crate::ServerRenderer;
 
     #[test]
@@ -1345,4 +3949,140 @@ mod ssr_tests {
             "<div>Hello, Jane!</div><div>Hello, John!</div><div>Hello, Josh!</div>"
         );
     }
+
+    #[test]
+    async fn test_text_back_to_back_hydratable() {
+        #[function_component]
+        fn Comp() -> Html {
+            let s = "world";
+
+            html! { <div>{"Hello "}{s}{"!"}<p></p>{"after"}</div> }
+        }
+
+        let renderer = ServerRenderer::<Comp>::new().hydratable(true);
+
+        let s = renderer.render().await;
+
+        assert_eq!(s, "<div>Hello <!---->world<!---->!<p></p>after</div>");
+    }
+
+    #[test]
+    async fn test_text_across_boundaries_hydratable() {
+        #[function_component]
+        fn Name() -> Html {
+            html! { "world" }
+        }
+
+        #[function_component]
+        fn Comp() -> Html {
+            html! { <div>{"Hello "}<Name />{"!"}<>{"a"}</>{"b"}<p></p>{"after"}</div> }
+        }
+
+        let renderer = ServerRenderer::<Comp>::new().hydratable(true);
+
+        let s = renderer.render().await;
+
+        assert_eq!(
+            s,
+            "<div>Hello <!---->world<!---->!<!---->a<!---->b<p></p>after</div>"
+        );
+    }
+
+    #[test]
+    async fn test_sort_by_key() {
+        #[derive(PartialEq, Properties, Debug)]
+        struct ListProps {
+            items: Vec<&'static str>,
+        }
+
+        #[function_component]
+        fn List(props: &ListProps) -> Html {
+            let mut list = VList::with_children(
+                props
+                    .items
+                    .iter()
+                    .map(|&item| html! { <li key={item}>{ item }</li> })
+                    .collect(),
+                None,
+            );
+            list.sort_by_key_in_ssr = true;
+
+            html! { <ul>{ list }</ul> }
+        }
+
+        let first = ServerRenderer::<List>::with_props(ListProps {
+            items: vec!["b", "c", "a"],
+        })
+        .render()
+        .await;
+        let second = ServerRenderer::<List>::with_props(ListProps {
+            items: vec!["c", "a", "b"],
+        })
+        .render()
+        .await;
+
+        assert_eq!(first, second);
+        assert_eq!(first, "<ul><li>a</li><li>b</li><li>c</li></ul>");
+    }
+
+    #[test]
+    async fn test_duplicate_keys() {
+        #[function_component]
+        fn Comp() -> Html {
+            html! {
+                <ul>
+                    <li key="a">{"a"}</li>
+                    <li key="b">{"b"}</li>
+                    <li key="a">{"c"}</li>
+                </ul>
+            }
+        }
+
+        let renderer = ServerRenderer::<Comp>::new();
+
+        let result = renderer.try_render().await;
+
+        assert_eq!(result, Err(RenderError::DuplicateKey("a".into())));
+
+        // Rendering without error handling still produces the markup
+        let rendered = ServerRenderer::<Comp>::new().render().await;
+        assert_eq!(rendered, "<ul><li>a</li><li>b</li><li>c</li></ul>");
+    }
+}
+
+#[cfg(all(test, feature = "wasm_test", feature = "ssr"))]
+mod hydration_tests {
+    use crate::prelude::*;
+    use crate::ServerRenderer;
+    use gloo_utils::document;
+    use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    async fn text_boundaries_survive_parsing() {
+        #[function_component]
+        fn Name() -> Html {
+            html! { "world" }
+        }
+
+        #[function_component]
+        fn Comp() -> Html {
+            html! { <div>{"Hello "}<Name />{"!"}<>{"a"}</>{"b"}</div> }
+        }
+
+        let renderer = ServerRenderer::<Comp>::new().hydratable(true);
+        let markup = renderer.render().await;
+
+        let parent = document().create_element("div").unwrap();
+        parent.set_inner_html(&markup);
+
+        let children = parent.first_child().unwrap().child_nodes();
+        let texts: Vec<String> = (0..children.length())
+            .filter_map(|i| children.get(i))
+            .filter(|node| node.node_type() == web_sys::Node::TEXT_NODE)
+            .filter_map(|node| node.text_content())
+            .collect();
+        assert_eq!(texts, ["Hello ", "world", "!", "a", "b"]);
+    }
 }
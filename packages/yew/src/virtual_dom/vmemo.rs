@@ -0,0 +1,254 @@
+//! This module contains the implementation of a memoized virtual node `VMemo`.
+
+use super::{VDiff, VNode};
+use crate::html::{AnyScope, NodeRef};
+use std::any::Any;
+use std::fmt;
+use std::rc::Rc;
+use web_sys::Node;
+
+/// A virtual node which is only diffed when its memo token changes.
+///
+/// When applied over an ancestor `VMemo` with an equal token, the wrapped node is not diffed at
+/// all and the already rendered ancestor node is reused. The token should be a cheap
+/// [`PartialEq`] value, e.g. a version number.
+///
+/// Components, portals and [nothing](crate::html::nothing) remember the node they were rendered
+/// before. Unless they are nested in an element, they have to learn about a new next sibling, so
+/// nodes containing them are always diffed.
+#[derive(Clone)]
+pub struct VMemo {
+    token: Rc<dyn Any>,
+    token_eq: fn(&dyn Any, &dyn Any) -> bool,
+    /// The memoized node.
+    pub node: Box<VNode>,
+}
+
+impl VMemo {
+    /// Creates a new memoized node which is only diffed when `token` changes.
+    pub fn new<T: PartialEq + 'static>(token: T, node: VNode) -> Self {
+        fn token_eq<T: PartialEq + 'static>(a: &dyn Any, b: &dyn Any) -> bool {
+            match (a.downcast_ref::<T>(), b.downcast_ref::<T>()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            }
+        }
+
+        Self {
+            token: Rc::new(token),
+            token_eq: token_eq::<T>,
+            node: Box::new(node),
+        }
+    }
+
+    /// Returns `true` if both memo tokens are equal.
+    pub fn same_token(&self, other: &VMemo) -> bool {
+        (self.token_eq)(&*self.token, &*other.token)
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    // Counts how often a memoized node skipped diffing
+    static MEMO_SKIPS: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+impl VDiff for VMemo {
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool) {
+        self.node.detach(parent, parent_to_detach);
+    }
+
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
+        self.node.shift(previous_parent, next_parent, next_sibling);
+    }
+
+    fn apply(
+        &mut self,
+        parent_scope: &AnyScope,
+        parent: &Node,
+        next_sibling: NodeRef,
+        ancestor: Option<VNode>,
+    ) -> NodeRef {
+        let ancestor = match ancestor {
+            Some(VNode::VMemo(ancestor)) => {
+                if self.same_token(&ancestor) && !ancestor.node.tracks_next_sibling() {
+                    #[cfg(test)]
+                    MEMO_SKIPS.with(|c| c.set(c.get() + 1));
+
+                    // Keep the rendered node, it still describes the DOM.
+                    self.node = ancestor.node;
+                    let node_ref = NodeRef::default();
+                    node_ref.set(self.node.first_node());
+                    return node_ref;
+                }
+                Some(*ancestor.node)
+            }
+            ancestor => ancestor,
+        };
+
+        self.node
+            .apply(parent_scope, parent, next_sibling, ancestor)
+    }
+}
+
+impl fmt::Debug for VMemo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VMemo").field("node", &self.node).finish()
+    }
+}
+
+impl PartialEq for VMemo {
+    fn eq(&self, other: &VMemo) -> bool {
+        self.same_token(other) && self.node == other.node
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    extern crate self as yew;
+
+    use super::*;
+    use crate::html;
+    use crate::tests::layout_tests::{diff_layouts, TestLayout};
+    use crate::virtual_dom::{AttrValue, VText};
+    use gloo_utils::document;
+
+    #[cfg(feature = "wasm_test")]
+    use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+    #[cfg(feature = "wasm_test")]
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn memo_list(items: &[(u32, &'static str)]) -> VNode {
+        items
+            .iter()
+            .map(|&(version, text)| VMemo::new(version, html! { <p>{ text }</p> }))
+            .collect()
+    }
+
+    #[test]
+    fn diff() {
+        let layout1 = TestLayout {
+            name: "1",
+            node: memo_list(&[(0, "a"), (0, "b")]),
+            expected: "<p>a</p><p>b</p>",
+        };
+
+        let layout2 = TestLayout {
+            name: "2",
+            node: memo_list(&[(0, "a"), (1, "c"), (0, "d")]),
+            expected: "<p>a</p><p>c</p><p>d</p>",
+        };
+
+        let layout3 = TestLayout {
+            name: "3",
+            node: html! { <>{ memo_list(&[(1, "e")]) }<i></i></> },
+            expected: "<p>e</p><i></i>",
+        };
+
+        diff_layouts(vec![layout1, layout2, layout3]);
+    }
+
+    #[test]
+    fn unchanged_unkeyed_memo_skips_diff() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut node = memo_list(&[(0, "a"), (0, "b"), (0, "c")]);
+        node.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.inner_html(), "<p>a</p><p>b</p><p>c</p>");
+
+        let skips = MEMO_SKIPS.with(|c| c.get());
+
+        // The content of the second item changed, but its token did not
+        let mut next = memo_list(&[(0, "a"), (0, "changed"), (1, "d")]);
+        next.apply(&scope, &parent, NodeRef::default(), Some(node));
+
+        assert_eq!(MEMO_SKIPS.with(|c| c.get()), skips + 2);
+        assert_eq!(parent.inner_html(), "<p>a</p><p>b</p><p>d</p>");
+        assert_eq!(
+            next,
+            memo_list(&[(0, "a"), (0, "b"), (1, "d")]),
+            "skipped nodes keep the rendered vdom"
+        );
+    }
+
+    #[test]
+    fn memo_with_component_is_diffed() {
+        use crate::{scheduler, Component, Context, Html};
+
+        struct Comp;
+
+        impl Component for Comp {
+            type Message = ();
+            type Properties = ();
+
+            fn create(_: &Context<Self>) -> Self {
+                Comp
+            }
+
+            fn view(&self, _: &Context<Self>) -> Html {
+                html! { <span></span> }
+            }
+        }
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let memo = || VNode::VMemo(VMemo::new(0, html! { <><Comp /></> }));
+
+        let mut node = html! { <>{ memo() }<i></i></> };
+        node.apply(&scope, &parent, NodeRef::default(), None);
+        scheduler::start_now();
+        assert_eq!(parent.inner_html(), "<span></span><i></i>");
+
+        let skips = MEMO_SKIPS.with(|c| c.get());
+
+        // The component has to be told about its new next sibling
+        let mut next = html! { <>{ memo() }<b></b></> };
+        next.apply(&scope, &parent, NodeRef::default(), Some(node));
+        scheduler::start_now();
+
+        assert_eq!(MEMO_SKIPS.with(|c| c.get()), skips);
+        assert_eq!(parent.inner_html(), "<span></span><b></b>");
+        match &next {
+            VNode::VList(list) => assert_eq!(
+                list[0].first_node(),
+                parent.first_child(),
+                "the component keeps its rendered node"
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn unchanged_memo_ancestor_is_not_cloned() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let text: Rc<str> = Rc::from("shared");
+        let memo = |text: &Rc<str>| -> VNode {
+            VNode::VMemo(VMemo::new(
+                0,
+                (0..100)
+                    .map(|_| VText::new(AttrValue::Rc(Rc::clone(text))))
+                    .collect(),
+            ))
+        };
+
+        let mut ancestor = memo(&text);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let mut node = memo(&text);
+        assert_eq!(Rc::strong_count(&text), 201);
+
+        let skips = MEMO_SKIPS.with(|c| c.get());
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+
+        // The rendered children of the ancestor are moved over, while the unrendered ones are
+        // dropped, without any copies made along the way
+        assert_eq!(MEMO_SKIPS.with(|c| c.get()), skips + 1);
+        assert_eq!(Rc::strong_count(&text), 101);
+        assert_eq!(parent.child_nodes().length(), 100);
+    }
+}
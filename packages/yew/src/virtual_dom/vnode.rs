@@ -1,14 +1,14 @@
 //! This module contains the implementation of abstract virtual node.
 
-use super::{Key, VChild, VComp, VDiff, VList, VPortal, VSuspense, VTag, VText};
+use super::{Key, VChild, VComp, VDiff, VList, VMemo, VNothing, VPortal, VSuspense, VTag, VText};
 use crate::html::{AnyScope, BaseComponent, NodeRef};
-use gloo::console;
 use std::cmp::PartialEq;
 use std::fmt;
 use std::iter::FromIterator;
+use std::panic::Location;
 use wasm_bindgen::JsCast;
 
-use web_sys::{Element, Node};
+use web_sys::Node;
 
 /// Bind virtual element to a DOM reference.
 #[derive(Clone)]
@@ -27,18 +27,38 @@ pub enum VNode {
     VRef(Node),
     /// A suspendible document fragment.
     VSuspense(VSuspense),
+    /// A node which is only diffed when its memo token changes.
+    VMemo(VMemo),
+    /// A node which renders no DOM node at all.
+    VNothing(VNothing),
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Number of times the key of a node was read
+    pub(crate) static KEY_READS: std::cell::Cell<usize> = std::cell::Cell::new(0);
 }
 
 impl VNode {
     pub fn key(&self) -> Option<Key> {
+        self.key_ref().cloned()
+    }
+
+    /// Returns a reference to the key of the [VNode], if it has one.
+    pub(crate) fn key_ref(&self) -> Option<&Key> {
+        #[cfg(test)]
+        KEY_READS.with(|c| c.set(c.get() + 1));
+
         match self {
-            VNode::VComp(vcomp) => vcomp.key.clone(),
-            VNode::VList(vlist) => vlist.key.clone(),
+            VNode::VComp(vcomp) => vcomp.key.as_ref(),
+            VNode::VList(vlist) => vlist.key.as_ref(),
             VNode::VRef(_) => None,
-            VNode::VTag(vtag) => vtag.key.clone(),
+            VNode::VTag(vtag) => vtag.key.as_ref(),
             VNode::VText(_) => None,
-            VNode::VPortal(vportal) => vportal.node.key(),
-            VNode::VSuspense(vsuspense) => vsuspense.key.clone(),
+            VNode::VPortal(vportal) => vportal.node.key_ref(),
+            VNode::VSuspense(vsuspense) => vsuspense.key.as_ref(),
+            VNode::VMemo(vmemo) => vmemo.node.key_ref(),
+            VNode::VNothing(_) => None,
         }
     }
 
@@ -47,10 +67,49 @@ impl VNode {
         match self {
             VNode::VComp(vcomp) => vcomp.key.is_some(),
             VNode::VList(vlist) => vlist.key.is_some(),
-            VNode::VRef(_) | VNode::VText(_) => false,
+            VNode::VRef(_) | VNode::VText(_) | VNode::VNothing(_) => false,
             VNode::VTag(vtag) => vtag.key.is_some(),
             VNode::VPortal(vportal) => vportal.node.has_key(),
             VNode::VSuspense(vsuspense) => vsuspense.key.is_some(),
+            VNode::VMemo(vmemo) => vmemo.node.has_key(),
+        }
+    }
+
+    /// Returns the location of the `html!` macro invocation, which created this node.
+    ///
+    /// This allows mapping rendered nodes back to the code which produced them when debugging.
+    /// Only elements and fragments track their location and only in debug builds.
+    pub fn source_location(&self) -> Option<&'static Location<'static>> {
+        match self {
+            VNode::VTag(vtag) => vtag.source_location(),
+            VNode::VList(vlist) => vlist.source_location(),
+            VNode::VMemo(vmemo) => vmemo.node.source_location(),
+            VNode::VComp(_)
+            | VNode::VText(_)
+            | VNode::VPortal(_)
+            | VNode::VRef(_)
+            | VNode::VSuspense(_)
+            | VNode::VNothing(_) => None,
+        }
+    }
+
+    /// Writes the virtual structure of the node, see
+    /// [VList::to_debug_string](super::VList::to_debug_string)
+    pub(crate) fn write_debug_string(&self, w: &mut String) {
+        match self {
+            VNode::VTag(vtag) => vtag.write_debug_string(w),
+            VNode::VText(vtext) => w.push_str(&format!("{:?}", &*vtext.text)),
+            VNode::VComp(vcomp) => vcomp.write_debug_string(w),
+            VNode::VList(vlist) => vlist.write_debug_string(w),
+            VNode::VPortal(vportal) => {
+                w.push_str("<#portal>");
+                vportal.node.write_debug_string(w);
+                w.push_str("</#portal>");
+            }
+            VNode::VRef(_) => w.push_str("<#ref />"),
+            VNode::VSuspense(vsuspense) => vsuspense.write_debug_string(w),
+            VNode::VMemo(vmemo) => vmemo.node.write_debug_string(w),
+            VNode::VNothing(_) => {}
         }
     }
 
@@ -68,6 +127,49 @@ impl VNode {
             VNode::VRef(node) => Some(node.clone()),
             VNode::VPortal(vportal) => vportal.next_sibling(),
             VNode::VSuspense(vsuspense) => vsuspense.first_node(),
+            VNode::VMemo(vmemo) => vmemo.node.first_node(),
+            VNode::VNothing(vnothing) => vnothing.next_sibling(),
+        }
+    }
+
+    /// Returns whether the node keeps a reference to the next sibling it was rendered before.
+    ///
+    /// Such nodes can not be kept without a diff when their next sibling might have changed.
+    /// Components, portals and [VNothing] keep one, unless they are nested in an element.
+    pub(crate) fn tracks_next_sibling(&self) -> bool {
+        match self {
+            VNode::VTag(_) | VNode::VText(_) | VNode::VRef(_) => false,
+            VNode::VComp(_) | VNode::VPortal(_) | VNode::VSuspense(_) | VNode::VNothing(_) => true,
+            VNode::VList(vlist) => vlist.iter().any(VNode::tracks_next_sibling),
+            VNode::VMemo(vmemo) => vmemo.node.tracks_next_sibling(),
+        }
+    }
+
+    /// Returns the number of DOM nodes rendered at the top level of this node
+    pub(crate) fn node_count(&self) -> usize {
+        match self {
+            VNode::VTag(_) | VNode::VText(_) | VNode::VRef(_) => 1,
+            VNode::VComp(vcomp) => vcomp.root_vnode().map_or(0, |node| node.node_count()),
+            VNode::VList(vlist) => vlist.iter().map(VNode::node_count).sum(),
+            VNode::VPortal(_) | VNode::VNothing(_) => 0,
+            VNode::VSuspense(vsuspense) => vsuspense.node_count(),
+            VNode::VMemo(vmemo) => vmemo.node.node_count(),
+        }
+    }
+
+    /// Returns the number of DOM nodes rendered at the top level of this node, without the
+    /// placeholders of empty fragments.
+    ///
+    /// Components, which were mounted but did not render yet, count as their placeholder node.
+    pub(crate) fn content_node_count(&self) -> usize {
+        match self {
+            VNode::VComp(vcomp) => vcomp
+                .root_vnode()
+                .map_or(1, |node| node.content_node_count()),
+            VNode::VList(vlist) => vlist.content_node_count(),
+            VNode::VSuspense(vsuspense) => vsuspense.content_node_count(),
+            VNode::VMemo(vmemo) => vmemo.node.content_node_count(),
+            node => node.node_count(),
         }
     }
 
@@ -102,10 +204,12 @@ impl VNode {
             VNode::VSuspense(vsuspense) => {
                 vsuspense.first_node().expect("VSuspense is not mounted")
             }
+            VNode::VMemo(vmemo) => vmemo.node.unchecked_first_node(),
+            VNode::VNothing(_) => panic!("VNothing has no first node"),
         }
     }
 
-    pub(crate) fn move_before(&self, parent: &Element, next_sibling: &Option<Node>) {
+    pub(crate) fn move_before(&self, parent: &Node, next_sibling: &Option<Node>) {
         match self {
             VNode::VList(vlist) => {
                 for node in vlist.iter() {
@@ -118,31 +222,43 @@ impl VNode {
                     .expect("VComp has no root vnode")
                     .move_before(parent, next_sibling);
             }
-            VNode::VPortal(_) => {} // no need to move portals
-            _ => super::insert_node(&self.unchecked_first_node(), parent, next_sibling.as_ref()),
+            VNode::VMemo(vmemo) => vmemo.node.move_before(parent, next_sibling),
+            VNode::VPortal(_) | VNode::VNothing(_) => {} // no need to move portals or nothing
+            _ => {
+                // The reference node can already have been removed or moved elsewhere by an
+                // overlapping update, in which case inserting before it would throw
+                let valid_next_sibling = next_sibling
+                    .as_ref()
+                    .filter(|next| next.parent_node().as_ref() == Some(parent));
+                #[cfg(debug_assertions)]
+                if next_sibling.is_some() && valid_next_sibling.is_none() {
+                    gloo::console::debug!("next sibling is no longer a child of parent, appending");
+                }
+                super::insert_node(&self.unchecked_first_node(), parent, valid_next_sibling)
+            }
         };
     }
 }
 
 impl VDiff for VNode {
     /// Remove VNode from parent.
-    fn detach(&mut self, parent: &Element, parent_to_detach: bool) {
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool) {
         match *self {
             VNode::VTag(ref mut vtag) => vtag.detach(parent, parent_to_detach),
             VNode::VText(ref mut vtext) => vtext.detach(parent, parent_to_detach),
             VNode::VComp(ref mut vcomp) => vcomp.detach(parent, parent_to_detach),
             VNode::VList(ref mut vlist) => vlist.detach(parent, parent_to_detach),
             VNode::VRef(ref node) => {
-                if parent.remove_child(node).is_err() {
-                    console::warn!("Node not found to remove VRef");
-                }
+                super::remove_node(node, parent);
             }
             VNode::VPortal(ref mut vportal) => vportal.detach(parent, parent_to_detach),
             VNode::VSuspense(ref mut vsuspense) => vsuspense.detach(parent, parent_to_detach),
+            VNode::VMemo(ref mut vmemo) => vmemo.detach(parent, parent_to_detach),
+            VNode::VNothing(ref mut vnothing) => vnothing.detach(parent, parent_to_detach),
         }
     }
 
-    fn shift(&self, previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef) {
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
         match *self {
             VNode::VTag(ref vtag) => vtag.shift(previous_parent, next_parent, next_sibling),
             VNode::VText(ref vtext) => vtext.shift(previous_parent, next_parent, next_sibling),
@@ -160,13 +276,17 @@ impl VDiff for VNode {
             VNode::VSuspense(ref vsuspense) => {
                 vsuspense.shift(previous_parent, next_parent, next_sibling)
             }
+            VNode::VMemo(ref vmemo) => vmemo.shift(previous_parent, next_parent, next_sibling),
+            VNode::VNothing(ref vnothing) => {
+                vnothing.shift(previous_parent, next_parent, next_sibling)
+            }
         }
     }
 
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
@@ -200,6 +320,12 @@ impl VDiff for VNode {
             VNode::VSuspense(ref mut vsuspense) => {
                 vsuspense.apply(parent_scope, parent, next_sibling, ancestor)
             }
+            VNode::VMemo(ref mut vmemo) => {
+                vmemo.apply(parent_scope, parent, next_sibling, ancestor)
+            }
+            VNode::VNothing(ref mut vnothing) => {
+                vnothing.apply(parent_scope, parent, next_sibling, ancestor)
+            }
         }
     }
 }
@@ -245,6 +371,20 @@ impl From<VSuspense> for VNode {
     }
 }
 
+impl From<VMemo> for VNode {
+    #[inline]
+    fn from(vmemo: VMemo) -> Self {
+        VNode::VMemo(vmemo)
+    }
+}
+
+impl From<VNothing> for VNode {
+    #[inline]
+    fn from(vnothing: VNothing) -> Self {
+        VNode::VNothing(vnothing)
+    }
+}
+
 impl<COMP> From<VChild<COMP>> for VNode
 where
     COMP: BaseComponent,
@@ -254,6 +394,10 @@ where
     }
 }
 
+/// Any displayable value is converted into a text node.
+///
+/// As this covers all types, an `Option<Html>` or a `Vec<Html>` is converted into [Html] through
+/// a fragment: `VList::from(option).into()`.
 impl<T: ToString> From<T> for VNode {
     fn from(value: T) -> Self {
         VNode::VText(VText::new(value.to_string()))
@@ -279,6 +423,8 @@ impl fmt::Debug for VNode {
             VNode::VRef(ref vref) => write!(f, "VRef ( \"{}\" )", crate::utils::print_node(vref)),
             VNode::VPortal(ref vportal) => vportal.fmt(f),
             VNode::VSuspense(ref vsuspense) => vsuspense.fmt(f),
+            VNode::VMemo(ref vmemo) => vmemo.fmt(f),
+            VNode::VNothing(ref vnothing) => vnothing.fmt(f),
         }
     }
 }
@@ -290,6 +436,8 @@ impl PartialEq for VNode {
             (VNode::VText(a), VNode::VText(b)) => a == b,
             (VNode::VList(a), VNode::VList(b)) => a == b,
             (VNode::VRef(a), VNode::VRef(b)) => a == b,
+            (VNode::VMemo(a), VNode::VMemo(b)) => a == b,
+            (VNode::VNothing(a), VNode::VNothing(b)) => a == b,
             // TODO: Need to improve PartialEq for VComp before enabling.
             (VNode::VComp(_), VNode::VComp(_)) => false,
             _ => false,
@@ -302,6 +450,8 @@ mod feat_ssr {
     use futures::future::{FutureExt, LocalBoxFuture};
 
     use super::*;
+    use crate::html::RenderResult;
+    use crate::virtual_dom::RenderOptions;
 
     impl VNode {
         // Boxing is needed here, due to: https://rust-lang.github.io/async-book/07_workarounds/04_recursion.html
@@ -309,13 +459,17 @@ mod feat_ssr {
             &'a self,
             w: &'a mut String,
             parent_scope: &'a AnyScope,
-        ) -> LocalBoxFuture<'a, ()> {
+            options: RenderOptions,
+        ) -> LocalBoxFuture<'a, RenderResult<()>> {
             async move {
                 match self {
-                    VNode::VTag(vtag) => vtag.render_to_string(w, parent_scope).await,
-                    VNode::VText(vtext) => vtext.render_to_string(w).await,
-                    VNode::VComp(vcomp) => vcomp.render_to_string(w, parent_scope).await,
-                    VNode::VList(vlist) => vlist.render_to_string(w, parent_scope).await,
+                    VNode::VTag(vtag) => vtag.render_to_string(w, parent_scope, options).await,
+                    VNode::VText(vtext) => {
+                        vtext.render_to_string(w).await;
+                        Ok(())
+                    }
+                    VNode::VComp(vcomp) => vcomp.render_to_string(w, parent_scope, options).await,
+                    VNode::VList(vlist) => vlist.render_to_string(w, parent_scope, options).await,
                     // We are pretty safe here as it's not possible to get a web_sys::Node without DOM
                     // support in the first place.
                     //
@@ -324,10 +478,13 @@ mod feat_ssr {
                     VNode::VRef(_) => {
                         panic!("VRef is not possible to be rendered in to a string.")
                     }
-                    // Portals are not rendered.
-                    VNode::VPortal(_) => {}
+                    // Portals and nothing nodes are not rendered.
+                    VNode::VPortal(_) | VNode::VNothing(_) => Ok(()),
                     VNode::VSuspense(vsuspense) => {
-                        vsuspense.render_to_string(w, parent_scope).await
+                        vsuspense.render_to_string(w, parent_scope, options).await
+                    }
+                    VNode::VMemo(vmemo) => {
+                        vmemo.node.render_to_string(w, parent_scope, options).await
                     }
                 }
             }
@@ -338,7 +495,11 @@ mod feat_ssr {
 
 #[cfg(test)]
 mod layout_tests {
+    extern crate self as yew;
+
     use super::*;
+    use crate::html;
+    use crate::html::Html;
     use crate::tests::layout_tests::{diff_layouts, TestLayout};
 
     #[cfg(feature = "wasm_test")]
@@ -367,4 +528,87 @@ mod layout_tests {
 
         diff_layouts(vec![layout1, layout2]);
     }
+    #[test]
+    fn move_before_stale_next_sibling_appends() {
+        let document = gloo_utils::document();
+        let parent = document.create_element("div").unwrap();
+        let a: Node = document.create_element("a").unwrap().into();
+        let b: Node = document.create_element("b").unwrap().into();
+        let stale: Node = document.create_element("i").unwrap().into();
+        parent.append_child(&a).unwrap();
+        parent.append_child(&b).unwrap();
+
+        // Never attached to `parent`
+        VNode::VRef(a.clone()).move_before(&parent, &Some(stale.clone()));
+        assert_eq!(parent.inner_html(), "<b></b><a></a>");
+
+        // Removed from `parent` before the move
+        parent.append_child(&stale).unwrap();
+        parent.remove_child(&stale).unwrap();
+        VNode::VRef(b.clone()).move_before(&parent, &Some(stale));
+        assert_eq!(parent.inner_html(), "<a></a><b></b>");
+
+        // A valid next sibling is still respected
+        VNode::VRef(b).move_before(&parent, &Some(a));
+        assert_eq!(parent.inner_html(), "<b></b><a></a>");
+    }
+
+    #[test]
+    fn into_html() {
+        fn render(node: impl Into<Html>) -> Html {
+            html! { <div>{ node.into() }</div> }
+        }
+
+        let some: Option<Html> = Some(html! { <b></b> });
+        let none: Option<Html> = None;
+        let layouts = vec![
+            TestLayout {
+                name: "&str",
+                node: render("text"),
+                expected: "<div>text</div>",
+            },
+            TestLayout {
+                name: "String",
+                node: render(String::from("owned")),
+                expected: "<div>owned</div>",
+            },
+            TestLayout {
+                name: "VText",
+                node: render(VText::new("vtext")),
+                expected: "<div>vtext</div>",
+            },
+            TestLayout {
+                name: "VTag",
+                node: render(VTag::new("i")),
+                expected: "<div><i></i></div>",
+            },
+            TestLayout {
+                name: "Html",
+                node: render(html! { <p>{ "html" }</p> }),
+                expected: "<div><p>html</p></div>",
+            },
+            TestLayout {
+                name: "Option<Html> - Some",
+                node: render(VList::from(some)),
+                expected: "<div><b></b></div>",
+            },
+            TestLayout {
+                name: "Option<Html> - None",
+                node: render(VList::from(none)),
+                expected: "<div></div>",
+            },
+            TestLayout {
+                name: "Vec<Html>",
+                node: render(VList::from(vec![html! { <i></i> }, html! { "text" }])),
+                expected: "<div><i></i>text</div>",
+            },
+            TestLayout {
+                name: "Iterator of Html",
+                node: render((0..3).map(|i| html! { <u>{ i }</u> }).collect::<Html>()),
+                expected: "<div><u>0</u><u>1</u><u>2</u></div>",
+            },
+        ];
+
+        diff_layouts(layouts);
+    }
 }
@@ -0,0 +1,146 @@
+//! This module contains the implementation of a virtual node rendering nothing, `VNothing`.
+
+use super::{VDiff, VNode};
+use crate::html::{AnyScope, NodeRef};
+use web_sys::Node;
+
+/// A virtual node, which renders no DOM node at all.
+///
+/// Unlike an empty [`VList`](super::VList), which stakes out its place with an empty text node,
+/// this only occupies a slot among its siblings. It is created by [`nothing`](crate::html::nothing).
+#[derive(Debug, Clone, Default)]
+pub struct VNothing {
+    /// The next sibling after this node. Set when rendered
+    sibling_ref: NodeRef,
+}
+
+impl VNothing {
+    /// Creates a new [VNothing].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the [Node] following this [VNothing], if this [VNothing]
+    /// has already been mounted in the DOM.
+    pub fn next_sibling(&self) -> Option<Node> {
+        self.sibling_ref.get()
+    }
+}
+
+impl VDiff for VNothing {
+    fn detach(&mut self, _parent: &Node, _parent_to_detach: bool) {
+        self.sibling_ref.set(None);
+    }
+
+    fn shift(&self, _previous_parent: &Node, _next_parent: &Node, _next_sibling: NodeRef) {
+        // There is nothing to move
+    }
+
+    fn apply(
+        &mut self,
+        _parent_scope: &AnyScope,
+        parent: &Node,
+        next_sibling: NodeRef,
+        ancestor: Option<VNode>,
+    ) -> NodeRef {
+        match ancestor {
+            Some(VNode::VNothing(_)) | None => {}
+            Some(mut node) => node.detach(parent, false),
+        }
+
+        self.sibling_ref = next_sibling.clone();
+        next_sibling
+    }
+}
+
+impl PartialEq for VNothing {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod layout_tests {
+    extern crate self as yew;
+
+    use crate::html;
+    use crate::html::nothing;
+    use crate::tests::layout_tests::{diff_layouts, TestLayout};
+
+    #[cfg(feature = "wasm_test")]
+    use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+    #[cfg(feature = "wasm_test")]
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn diff() {
+        let layout1 = TestLayout {
+            name: "1",
+            node: html! {
+                <>
+                    <i></i>
+                    { nothing() }
+                    <b></b>
+                </>
+            },
+            expected: "<i></i><b></b>",
+        };
+
+        let layout2 = TestLayout {
+            name: "2",
+            node: html! {
+                <>
+                    <i></i>
+                    <p></p>
+                    <b></b>
+                </>
+            },
+            expected: "<i></i><p></p><b></b>",
+        };
+
+        let layout3 = TestLayout {
+            name: "3",
+            node: html! {
+                <>
+                    { nothing() }
+                    { nothing() }
+                    <b></b>
+                </>
+            },
+            expected: "<b></b>",
+        };
+
+        let layout4 = TestLayout {
+            name: "4",
+            node: html! {
+                <>
+                    <i></i>
+                    { nothing() }
+                </>
+            },
+            expected: "<i></i>",
+        };
+
+        diff_layouts(vec![layout1, layout2, layout3, layout4]);
+    }
+
+    #[test]
+    fn adds_no_dom_node() {
+        use crate::html::AnyScope;
+        use crate::virtual_dom::VDiff;
+        use crate::NodeRef;
+
+        let scope = AnyScope::test();
+        let parent = gloo_utils::document().create_element("div").unwrap();
+
+        let mut node = html! { <>{ nothing() }{ nothing() }</> };
+        node.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.child_nodes().length(), 0);
+
+        // An empty fragment stakes out its place with a placeholder instead
+        let mut empty = html! { <></> };
+        empty.apply(&scope, &parent, NodeRef::default(), Some(node));
+        assert_eq!(parent.child_nodes().length(), 1);
+    }
+}
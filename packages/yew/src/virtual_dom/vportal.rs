@@ -2,12 +2,13 @@
 
 use super::{VDiff, VNode};
 use crate::html::{AnyScope, NodeRef};
-use web_sys::{Element, Node};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node, ShadowRoot};
 
 #[derive(Debug, Clone)]
 pub struct VPortal {
-    /// The element under which the content is inserted.
-    pub host: Element,
+    /// The element or shadow root under which the content is inserted.
+    pub host: Node,
     /// The next sibling after the inserted content
     pub next_sibling: NodeRef,
     /// The inserted node
@@ -17,22 +18,26 @@ pub struct VPortal {
 }
 
 impl VDiff for VPortal {
-    fn detach(&mut self, _: &Element, _parent_to_detach: bool) {
+    fn detach(&mut self, _: &Node, _parent_to_detach: bool) {
         self.node.detach(&self.host, false);
         self.sibling_ref.set(None);
     }
 
-    fn shift(&self, _previous_parent: &Element, _next_parent: &Element, _next_sibling: NodeRef) {
+    fn shift(&self, _previous_parent: &Node, _next_parent: &Node, _next_sibling: NodeRef) {
         // portals have nothing in it's original place of DOM, we also do nothing.
     }
 
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
+        if let Some(shadow_root) = self.host.dyn_ref::<ShadowRoot>() {
+            super::listeners::register_shadow_root(shadow_root);
+        }
+
         let inner_ancestor = match ancestor {
             Some(VNode::VPortal(old_portal)) => {
                 let VPortal {
@@ -75,7 +80,7 @@ impl VPortal {
     /// Creates a [VPortal] rendering `content` in the DOM hierarchy under `host`.
     pub fn new(content: VNode, host: Element) -> Self {
         Self {
-            host,
+            host: host.into(),
             next_sibling: NodeRef::default(),
             node: Box::new(content),
             sibling_ref: NodeRef::default(),
@@ -86,7 +91,7 @@ impl VPortal {
     /// The parent of `next_sibling`, if given, must be `host`.
     pub fn new_before(content: VNode, host: Element, next_sibling: Option<Node>) -> Self {
         Self {
-            host,
+            host: host.into(),
             next_sibling: {
                 let sib_ref = NodeRef::default();
                 sib_ref.set(next_sibling);
@@ -96,6 +101,15 @@ impl VPortal {
             sibling_ref: NodeRef::default(),
         }
     }
+    /// Creates a [VPortal] rendering `content` into the shadow tree of `shadow_root`.
+    pub fn new_in_shadow_root(content: VNode, shadow_root: ShadowRoot) -> Self {
+        Self {
+            host: shadow_root.into(),
+            next_sibling: NodeRef::default(),
+            node: Box::new(content),
+            sibling_ref: NodeRef::default(),
+        }
+    }
     /// Returns the [Node] following this [VPortal], if this [VPortal]
     /// has already been mounted in the DOM.
     pub fn next_sibling(&self) -> Option<Node> {
@@ -103,6 +117,50 @@ impl VPortal {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    extern crate self as yew;
+
+    use super::*;
+    use crate::html;
+    use gloo_utils::document;
+    use web_sys::{ShadowRootInit, ShadowRootMode};
+
+    #[cfg(feature = "wasm_test")]
+    use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+
+    #[cfg(feature = "wasm_test")]
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn render_into_shadow_root() {
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+        let host = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&host).unwrap();
+        let shadow_root = host
+            .attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))
+            .unwrap();
+
+        let mut portal = VNode::VPortal(VPortal::new_in_shadow_root(
+            html! { <><p>{"first"}</p><p>{"second"}</p></> },
+            shadow_root.clone(),
+        ));
+        portal.apply(&scope, &parent, NodeRef::default(), None);
+
+        assert_eq!(shadow_root.inner_html(), "<p>first</p><p>second</p>");
+        assert_eq!(
+            shadow_root.first_child().unwrap().parent_node().unwrap(),
+            Node::from(shadow_root.clone())
+        );
+        assert_eq!(host.inner_html(), "");
+        assert_eq!(parent.inner_html(), "");
+
+        portal.detach(&parent, false);
+        assert_eq!(shadow_root.inner_html(), "");
+    }
+}
+
 #[cfg(test)]
 mod layout_tests {
     extern crate self as yew;
@@ -45,10 +45,39 @@ impl VSuspense {
             self.children.first_node()
         }
     }
+
+    /// Writes the rendered branch, see [VList::to_debug_string](super::VList::to_debug_string)
+    pub(crate) fn write_debug_string(&self, w: &mut String) {
+        w.push_str("<#suspense");
+        super::write_debug_key(w, self.key.as_ref());
+        w.push('>');
+        if self.suspended {
+            self.fallback.write_debug_string(w);
+        } else {
+            self.children.write_debug_string(w);
+        }
+        w.push_str("</#suspense>");
+    }
+
+    pub(crate) fn node_count(&self) -> usize {
+        if self.suspended {
+            self.fallback.node_count()
+        } else {
+            self.children.node_count()
+        }
+    }
+
+    pub(crate) fn content_node_count(&self) -> usize {
+        if self.suspended {
+            self.fallback.content_node_count()
+        } else {
+            self.children.content_node_count()
+        }
+    }
 }
 
 impl VDiff for VSuspense {
-    fn detach(&mut self, parent: &Element, parent_to_detach: bool) {
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool) {
         if self.suspended {
             self.fallback.detach(parent, parent_to_detach);
             if let Some(ref m) = self.detached_parent {
@@ -59,7 +88,7 @@ impl VDiff for VSuspense {
         }
     }
 
-    fn shift(&self, previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef) {
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
         if self.suspended {
             self.fallback
                 .shift(previous_parent, next_parent, next_sibling);
@@ -72,7 +101,7 @@ impl VDiff for VSuspense {
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
@@ -153,17 +182,27 @@ impl VDiff for VSuspense {
 #[cfg(feature = "ssr")]
 mod feat_ssr {
     use super::*;
+    use crate::html::RenderResult;
+    use crate::virtual_dom::RenderOptions;
 
     impl VSuspense {
-        pub(crate) async fn render_to_string(&self, w: &mut String, parent_scope: &AnyScope) {
+        pub(crate) async fn render_to_string(
+            &self,
+            w: &mut String,
+            parent_scope: &AnyScope,
+            options: RenderOptions,
+        ) -> RenderResult<()> {
             // always render children on the server side.
-            self.children.render_to_string(w, parent_scope).await;
+            self.children
+                .render_to_string(w, parent_scope, options)
+                .await
         }
     }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32"), feature = "ssr"))]
 mod ssr_tests {
+    use std::cell::RefCell;
     use std::rc::Rc;
     use std::time::Duration;
 
@@ -180,21 +219,26 @@ mod ssr_tests {
         #[derive(PartialEq)]
         pub struct SleepState {
             s: Suspension,
+            /// Stands in for data fetched while suspended
+            fetched: Rc<RefCell<Option<String>>>,
         }
 
         impl SleepState {
             fn new() -> Self {
                 let (s, handle) = Suspension::new();
+                let fetched = Rc::<RefCell<Option<String>>>::default();
 
                 // we use tokio spawn local here.
+                let data = fetched.clone();
                 spawn_local(async move {
                     // we use tokio sleep here.
                     sleep(Duration::from_millis(50)).await;
+                    *data.borrow_mut() = Some("fetched".to_string());
 
                     handle.resume();
                 });
 
-                Self { s }
+                Self { s, fetched }
             }
         }
 
@@ -207,11 +251,12 @@ mod ssr_tests {
         }
 
         #[hook]
-        pub fn use_sleep() -> SuspensionResult<Rc<dyn Fn()>> {
+        pub fn use_sleep() -> SuspensionResult<(Rc<dyn Fn()>, String)> {
             let sleep_state = use_reducer(SleepState::new);
 
             if sleep_state.s.resumed() {
-                Ok(Rc::new(move || sleep_state.dispatch(())))
+                let fetched = sleep_state.fetched.borrow().clone().unwrap();
+                Ok((Rc::new(move || sleep_state.dispatch(())), fetched))
             } else {
                 Err(sleep_state.s.clone())
             }
@@ -224,8 +269,8 @@ mod ssr_tests {
 
         #[function_component]
         fn Child(props: &ChildProps) -> HtmlResult {
-            use_sleep()?;
-            Ok(html! { <div>{"Hello, "}{&props.name}{"!"}</div> })
+            let (_, fetched) = use_sleep()?;
+            Ok(html! { <div data-fetched={fetched}>{"Hello, "}{&props.name}{"!"}</div> })
         }
 
         #[function_component]
@@ -251,9 +296,12 @@ mod ssr_tests {
             })
             .await;
 
+        // Rendered with the data fetched while suspended instead of the fallback
         assert_eq!(
             s,
-            "<div>Hello, Jane!</div><div>Hello, John!</div><div>Hello, Josh!</div>"
+            "<div data-fetched=\"fetched\">Hello, Jane!</div>\
+             <div data-fetched=\"fetched\">Hello, John!</div>\
+             <div data-fetched=\"fetched\">Hello, Josh!</div>"
         );
     }
 }
@@ -2,17 +2,22 @@
 
 use super::{Apply, AttrValue, Attributes, Key, Listener, Listeners, VDiff, VList, VNode};
 use crate::html::{AnyScope, IntoPropValue, NodeRef};
-use gloo::console;
 use gloo_utils::document;
 use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::cmp::PartialEq;
+use std::collections::HashMap;
+use std::fmt;
 use std::hint::unreachable_unchecked;
 use std::marker::PhantomData;
 use std::mem;
 use std::ops::Deref;
+use std::panic::Location;
 use std::rc::Rc;
 use wasm_bindgen::JsCast;
-use web_sys::{Element, HtmlInputElement as InputElement, HtmlTextAreaElement as TextAreaElement};
+use web_sys::{
+    Element, HtmlInputElement as InputElement, HtmlTextAreaElement as TextAreaElement, Node,
+};
 
 /// SVG namespace string used for creating svg elements
 pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
@@ -20,6 +25,148 @@ pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
 /// Default namespace for html elements
 pub const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
 
+/// MathML namespace string used for creating math elements
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Maximum amount of detached elements kept per namespace and tag name
+const MAX_POOLED_ELEMENTS: usize = 64;
+
+/// HTML elements, which carry no state beyond their attributes and children and can be pooled.
+///
+/// Form controls, media, embedded content and custom elements keep state that is not reset by
+/// clearing attributes, so they are never pooled.
+const POOLED_HTML_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "article",
+    "aside",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "code",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "i",
+    "img",
+    "label",
+    "li",
+    "main",
+    "mark",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "section",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+/// SVG elements, which can be pooled, see [POOLED_HTML_TAGS]
+const POOLED_SVG_TAGS: &[&str] = &[
+    "circle", "ellipse", "g", "line", "path", "polygon", "polyline", "rect", "text", "tspan",
+];
+
+thread_local! {
+    /// Reuse detached elements when creating new ones
+    static POOL_ELEMENTS: Cell<bool> = Cell::new(false);
+
+    /// Detached elements available for reuse, keyed by namespace and tag name
+    static ELEMENT_POOL: RefCell<HashMap<(&'static str, String), Vec<Element>>> = Default::default();
+}
+
+/// Set, if elements removed from the DOM should be pooled and reused for new elements with the
+/// same tag name.
+///
+/// Pooling is disabled by default. Enabling it can reduce allocator and garbage collector pressure
+/// in lists with a high churn of identical elements, like infinite scrolling. Pooled elements are
+/// stripped of their attributes and children before being reused. Only plain HTML and SVG
+/// elements without a shadow root are pooled, as other elements keep state that can not be reset.
+///
+/// Pooling is set for the current thread. Disabling it drops all currently pooled elements.
+pub fn set_element_pooling(pool: bool) {
+    POOL_ELEMENTS.with(|p| p.set(pool));
+    if !pool {
+        ELEMENT_POOL.with(|p| p.borrow_mut().clear());
+    }
+}
+
+/// Returns `true`, if detached elements are returned to the pool
+pub(crate) fn pooling_elements() -> bool {
+    POOL_ELEMENTS.with(Cell::get)
+}
+
+/// Take a pooled element for `tag` in `namespace`, if pooling is enabled and one is available
+fn take_pooled_element(namespace: &'static str, tag: &str) -> Option<Element> {
+    if !pooling_elements() {
+        return None;
+    }
+    ELEMENT_POOL.with(|p| {
+        p.borrow_mut()
+            .get_mut(&(namespace, tag.to_owned()))
+            .and_then(Vec::pop)
+    })
+}
+
+/// Reset a detached element and return it to the pool, if pooling is enabled
+fn recycle_element(el: Element) {
+    if !pooling_elements() {
+        return;
+    }
+    let (namespace, pooled_tags) = match el.namespace_uri().as_deref() {
+        Some(SVG_NAMESPACE) => (SVG_NAMESPACE, POOLED_SVG_TAGS),
+        Some(HTML_NAMESPACE) => (HTML_NAMESPACE, POOLED_HTML_TAGS),
+        // Do not pool foreign elements
+        _ => return,
+    };
+    if !pooled_tags.contains(&el.local_name().as_str()) || el.shadow_root().is_some() {
+        return;
+    }
+
+    el.set_text_content(None);
+    for name in el.get_attribute_names().iter() {
+        if let Some(name) = name.as_string() {
+            let _ = el.remove_attribute(&name);
+        }
+    }
+
+    ELEMENT_POOL.with(|p| {
+        let mut pool = p.borrow_mut();
+        let pooled = pool.entry((namespace, el.local_name())).or_default();
+        if pooled.len() < MAX_POOLED_ELEMENTS {
+            pooled.push(el);
+        }
+    });
+}
+
 // Value field corresponding to an [Element]'s `value` property
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct Value<T: AccessValue>(Option<AttrValue>, PhantomData<T>);
@@ -140,9 +287,22 @@ enum VTagInner {
 
         /// List of child nodes
         children: VList,
+
+        /// Text set as the element's `textContent` instead of rendering `children`
+        text: Option<AttrValue>,
     },
 }
 
+/// A callback receiving the element of a [VTag], see [VTag::set_on_mount]
+#[derive(Clone)]
+struct ElementHook(Rc<dyn Fn(&Element)>);
+
+impl fmt::Debug for ElementHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ElementHook(_)")
+    }
+}
+
 /// A type for a virtual
 /// [Element](https://developer.mozilla.org/en-US/docs/Web/API/Element)
 /// representation.
@@ -164,6 +324,16 @@ pub struct VTag {
     pub attributes: Attributes,
 
     pub key: Option<Key>,
+
+    /// Called with the element after it was created and inserted
+    on_mount: Option<ElementHook>,
+
+    /// Called with the element before it is removed
+    on_unmount: Option<ElementHook>,
+
+    /// Where this tag was created by the `html!` macro
+    #[cfg(debug_assertions)]
+    source_location: Option<&'static Location<'static>>,
 }
 
 impl Clone for VTag {
@@ -175,6 +345,10 @@ impl Clone for VTag {
             attributes: self.attributes.clone(),
             node_ref: self.node_ref.clone(),
             key: self.key.clone(),
+            on_mount: self.on_mount.clone(),
+            on_unmount: self.on_unmount.clone(),
+            #[cfg(debug_assertions)]
+            source_location: self.source_location,
         }
     }
 }
@@ -192,6 +366,7 @@ impl VTag {
                 _ => VTagInner::Other {
                     tag,
                     children: Default::default(),
+                    text: None,
                 },
             },
             Default::default(),
@@ -281,7 +456,11 @@ impl VTag {
         children: VList,
     ) -> Self {
         VTag::new_base(
-            VTagInner::Other { tag, children },
+            VTagInner::Other {
+                tag,
+                children,
+                text: None,
+            },
             node_ref,
             key,
             attributes,
@@ -306,6 +485,10 @@ impl VTag {
             listeners,
             node_ref,
             key,
+            on_mount: None,
+            on_unmount: None,
+            #[cfg(debug_assertions)]
+            source_location: None,
         }
     }
 
@@ -318,20 +501,148 @@ impl VTag {
         }
     }
 
+    /// Sets a callback, which is called with the element once it was created and inserted into
+    /// the DOM, along with its children.
+    ///
+    /// This allows imperative setup of the element, e.g. initializing a chart. Patching the
+    /// element or moving it within a keyed list does not call it again.
+    pub fn set_on_mount(&mut self, on_mount: impl Fn(&Element) + 'static) {
+        self.on_mount = Some(ElementHook(Rc::new(on_mount)));
+    }
+
+    /// Sets a callback, which is called with the element before it is removed from the DOM.
+    ///
+    /// Like [`set_on_mount`](Self::set_on_mount), this is called once per element and not
+    /// when the element is moved within a keyed list.
+    pub fn set_on_unmount(&mut self, on_unmount: impl Fn(&Element) + 'static) {
+        self.on_unmount = Some(ElementHook(Rc::new(on_unmount)));
+    }
+
+    /// Unmounts the rendered element from this tag, before it is patched into a tag of another
+    /// child, see [`VList::recycle_tags`](super::VList::recycle_tags).
+    pub(crate) fn unmount_for_recycling(&mut self) {
+        if let Some(el) = &self.reference {
+            if let Some(ElementHook(on_unmount)) = &self.on_unmount {
+                on_unmount(el);
+            }
+            if self.node_ref.get().as_ref() == Some(el) {
+                self.node_ref.set(None);
+            }
+        }
+    }
+
+    /// Mounts the element this tag was patched with, after recycling it from another tag.
+    pub(crate) fn mount_recycled(&self) {
+        if let (Some(el), Some(ElementHook(on_mount))) = (&self.reference, &self.on_mount) {
+            on_mount(el);
+        }
+    }
+
+    /// Returns the location of the `html!` macro invocation this tag was created by.
+    ///
+    /// Source locations are only tracked in debug builds and are always `None` otherwise.
+    #[cfg(debug_assertions)]
+    pub fn source_location(&self) -> Option<&'static Location<'static>> {
+        self.source_location
+    }
+
+    /// Returns the location of the `html!` macro invocation this tag was created by.
+    ///
+    /// Source locations are only tracked in debug builds and are always `None` otherwise.
+    #[cfg(not(debug_assertions))]
+    pub fn source_location(&self) -> Option<&'static Location<'static>> {
+        None
+    }
+
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn __macro_set_source_location(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            self.source_location = Some(Location::caller());
+        }
+    }
+
     /// Add [VNode] child.
+    ///
+    /// Clears the text set with [`set_text_content`](Self::set_text_content).
     pub fn add_child(&mut self, child: VNode) {
-        if let VTagInner::Other { children, .. } = &mut self.inner {
+        if let VTagInner::Other { children, text, .. } = &mut self.inner {
+            *text = None;
             children.add_child(child);
         }
     }
 
     /// Add multiple [VNode] children.
+    ///
+    /// Clears the text set with [`set_text_content`](Self::set_text_content).
     pub fn add_children(&mut self, children: impl IntoIterator<Item = VNode>) {
-        if let VTagInner::Other { children: dst, .. } = &mut self.inner {
+        if let VTagInner::Other {
+            children: dst,
+            text,
+            ..
+        } = &mut self.inner
+        {
+            *text = None;
             dst.add_children(children)
         }
     }
 
+    /// Returns the text set with [`set_text_content`](Self::set_text_content).
+    pub fn text_content(&self) -> Option<&AttrValue> {
+        match &self.inner {
+            VTagInner::Other { text, .. } => text.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the element's `textContent` directly, replacing any children.
+    ///
+    /// This is cheaper than a single [VText](crate::virtual_dom::VText) child for elements
+    /// which only contain dynamic text, as no child node has to be tracked and diffed.
+    /// Passing `None` or adding children switches back to rendering children.
+    ///
+    /// Does nothing for `<input>` and `<textarea>`, use [`set_value`](Self::set_value) instead.
+    pub fn set_text_content(&mut self, value: impl IntoPropValue<Option<AttrValue>>) {
+        if let VTagInner::Other { children, text, .. } = &mut self.inner {
+            *text = value.into_prop_value();
+            if text.is_some() {
+                *children = VList::new();
+            }
+        }
+    }
+
+    /// Writes the tag with its key, attributes and children, see
+    /// [VList::to_debug_string](super::VList::to_debug_string)
+    pub(crate) fn write_debug_string(&self, w: &mut String) {
+        w.push('<');
+        w.push_str(self.tag());
+        super::write_debug_key(w, self.key.as_ref());
+        for (name, value) in self.attributes.iter() {
+            w.push_str(&format!(" {}={:?}", name, value));
+        }
+        if let Some(value) = self.value() {
+            w.push_str(&format!(" value={:?}", &**value));
+        }
+        match &self.inner {
+            VTagInner::Other { text, children, .. } => {
+                w.push('>');
+                match text {
+                    Some(text) => w.push_str(&format!("{:?}", &**text)),
+                    None => {
+                        for child in children.iter() {
+                            child.write_debug_string(w);
+                        }
+                    }
+                }
+                w.push_str("</");
+                w.push_str(self.tag());
+                w.push('>');
+            }
+            VTagInner::Input(_) | VTagInner::Textarea { .. } => w.push_str(" />"),
+        }
+    }
+
     /// Returns a reference to the children of this [VTag]
     pub fn children(&self) -> &VList {
         match &self.inner {
@@ -347,10 +658,15 @@ impl VTag {
     }
 
     /// Returns a mutable reference to the children of this [VTag], if the node can have
-    // children
+    /// children.
+    ///
+    /// Clears the text set with [`set_text_content`](Self::set_text_content).
     pub fn children_mut(&mut self) -> Option<&mut VList> {
         match &mut self.inner {
-            VTagInner::Other { children, .. } => Some(children),
+            VTagInner::Other { children, text, .. } => {
+                *text = None;
+                Some(children)
+            }
             _ => None,
         }
     }
@@ -455,32 +771,86 @@ impl VTag {
         self.listeners = Listeners::Pending(listeners);
     }
 
-    fn create_element(&self, parent: &Element) -> Element {
+    fn create_element(&self, parent: &Node) -> Element {
         let tag = self.tag();
-        if tag == "svg"
-            || parent
-                .namespace_uri()
-                .map_or(false, |ns| ns == SVG_NAMESPACE)
-        {
-            let namespace = Some(SVG_NAMESPACE);
-            document()
-                .create_element_ns(namespace, tag)
-                .expect("can't create namespaced element for vtag")
-        } else {
+        let namespace = self.namespace(parent);
+        if namespace == HTML_NAMESPACE {
+            if let Some(el) = self.take_pooled_element(HTML_NAMESPACE) {
+                return el;
+            }
             document()
                 .create_element(tag)
                 .expect("can't create element for vtag")
+        } else {
+            if namespace == SVG_NAMESPACE {
+                if let Some(el) = self.take_pooled_element(SVG_NAMESPACE) {
+                    return el;
+                }
+            }
+            document()
+                .create_element_ns(Some(&namespace), tag)
+                .expect("can't create namespaced element for vtag")
+        }
+    }
+
+    /// Returns the namespace to create the element in.
+    ///
+    /// It is chosen per tag, so siblings of a fragment can live in different namespaces. An
+    /// explicit `xmlns` attribute takes precedence, `<svg>` and `<math>` start foreign content and
+    /// all other tags inherit the namespace of their parent, except for the HTML content of a
+    /// `<foreignObject>`.
+    fn namespace(&self, parent: &Node) -> Cow<'_, str> {
+        if let Some((_, namespace)) = self.attributes.iter().find(|(k, _)| *k == "xmlns") {
+            return Cow::Borrowed(namespace);
+        }
+        match self.tag() {
+            "svg" => return Cow::Borrowed(SVG_NAMESPACE),
+            "math" => return Cow::Borrowed(MATHML_NAMESPACE),
+            _ => (),
+        }
+        // The content of a shadow root is always HTML
+        let parent = match parent.dyn_ref::<Element>() {
+            Some(parent) => parent,
+            None => return Cow::Borrowed(HTML_NAMESPACE),
+        };
+        match parent.namespace_uri() {
+            Some(ns)
+                if ns == SVG_NAMESPACE
+                    && parent.local_name().eq_ignore_ascii_case("foreignObject") =>
+            {
+                Cow::Borrowed(HTML_NAMESPACE)
+            }
+            Some(ns) => Cow::Owned(ns),
+            None => Cow::Borrowed(HTML_NAMESPACE),
+        }
+    }
+
+    /// Only elements without special fields are pooled, as those carry state that can not be
+    /// reset through attributes.
+    fn take_pooled_element(&self, namespace: &'static str) -> Option<Element> {
+        match &self.inner {
+            // HTML tag names are case-insensitive, SVG ones are not
+            VTagInner::Other { tag, .. } if namespace == HTML_NAMESPACE => {
+                take_pooled_element(namespace, &tag.to_ascii_lowercase())
+            }
+            VTagInner::Other { tag, .. } => take_pooled_element(namespace, tag),
+            _ => None,
         }
     }
 }
 
 impl VDiff for VTag {
     /// Remove VTag from parent.
-    fn detach(&mut self, parent: &Element, parent_to_detach: bool) {
-        let node = self
-            .reference
-            .take()
-            .expect("tried to remove not rendered VTag from DOM");
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool) {
+        let node = match self.reference.take() {
+            Some(node) => node,
+            // Already detached
+            None => return,
+        };
+
+        if let Some(ElementHook(on_unmount)) = &self.on_unmount {
+            on_unmount(&node);
+        }
 
         self.listeners.unregister();
 
@@ -489,21 +859,19 @@ impl VDiff for VTag {
             // This tag will be removed, so there's no point to remove any child.
             children.detach(&node, true);
         }
-        if !parent_to_detach {
-            let result = parent.remove_child(&node);
-
-            if result.is_err() {
-                console::warn!("Node not found to remove VTag");
-            }
-        }
         // It could be that the ref was already reused when rendering another element.
         // Only unset the ref it still belongs to our node
         if self.node_ref.get().as_ref() == Some(&node) {
             self.node_ref.set(None);
         }
+        if !parent_to_detach && super::remove_node(&node, parent) {
+            if let VTagInner::Other { .. } = self.inner {
+                recycle_element(node);
+            }
+        }
     }
 
-    fn shift(&self, previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef) {
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
         let node = self
             .reference
             .as_ref()
@@ -520,7 +888,7 @@ impl VDiff for VTag {
     fn apply(
         &mut self,
         parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
@@ -571,6 +939,7 @@ impl VDiff for VTag {
             }),
         };
 
+        let mounted = ancestor_tag.is_none();
         match ancestor_tag {
             None => {
                 self.attributes.apply(&el);
@@ -583,6 +952,11 @@ impl VDiff for VTag {
                     VTagInner::Textarea { value } => {
                         value.apply(el.unchecked_ref());
                     }
+                    VTagInner::Other {
+                        text: Some(text), ..
+                    } => {
+                        el.set_text_content(Some(&**text));
+                    }
                     VTagInner::Other { children, .. } => {
                         if !children.is_empty() {
                             children.apply(parent_scope, &el, NodeRef::default(), None);
@@ -601,12 +975,34 @@ impl VDiff for VTag {
                     (VTagInner::Textarea { value: new }, VTagInner::Textarea { value: old }) => {
                         new.apply_diff(el.unchecked_ref(), old);
                     }
+                    (
+                        VTagInner::Other {
+                            text: Some(new), ..
+                        },
+                        VTagInner::Other {
+                            children: mut old,
+                            text: old_text,
+                            ..
+                        },
+                    ) => {
+                        if old_text.as_ref() != Some(&*new) {
+                            // Replaces all child nodes, so the old children only need to be
+                            // detached virtually
+                            old.detach(&el, true);
+                            el.set_text_content(Some(&**new));
+                        }
+                    }
                     (
                         VTagInner::Other { children: new, .. },
                         VTagInner::Other {
-                            children: mut old, ..
+                            children: mut old,
+                            text: old_text,
+                            ..
                         },
                     ) => {
+                        if old_text.is_some() {
+                            el.set_text_content(None);
+                        }
                         if !new.is_empty() {
                             new.apply(parent_scope, &el, NodeRef::default(), Some(old.into()));
                         } else if !old.is_empty() {
@@ -619,6 +1015,12 @@ impl VDiff for VTag {
             }
         };
 
+        if mounted {
+            if let Some(ElementHook(on_mount)) = &self.on_mount {
+                on_mount(&el);
+            }
+        }
+
         self.node_ref.set(Some(el.deref().clone()));
         self.reference = el.into();
         self.node_ref.clone()
@@ -635,7 +1037,18 @@ impl PartialEq for VTag {
                 Input (r),
             ) => l == r,
             (Textarea { value: value_l }, Textarea { value: value_r }) => value_l == value_r,
-            (Other { tag: tag_l, .. }, Other { tag: tag_r, .. }) => tag_l == tag_r,
+            (
+                Other {
+                    tag: tag_l,
+                    text: text_l,
+                    ..
+                },
+                Other {
+                    tag: tag_r,
+                    text: text_r,
+                    ..
+                },
+            ) => tag_l == tag_r && text_l == text_r,
             _ => false,
         }) && self.listeners.eq(&other.listeners)
             && self.attributes == other.attributes
@@ -650,11 +1063,18 @@ impl PartialEq for VTag {
 #[cfg(feature = "ssr")]
 mod feat_ssr {
     use super::*;
+    use crate::html::RenderResult;
+    use crate::virtual_dom::RenderOptions;
     use crate::virtual_dom::VText;
     use std::fmt::Write;
 
     impl VTag {
-        pub(crate) async fn render_to_string(&self, w: &mut String, parent_scope: &AnyScope) {
+        pub(crate) async fn render_to_string(
+            &self,
+            w: &mut String,
+            parent_scope: &AnyScope,
+            options: RenderOptions,
+        ) -> RenderResult<()> {
             write!(w, "<{}", self.tag()).unwrap();
 
             let write_attr = |w: &mut String, name: &str, val: Option<&str>| {
@@ -693,13 +1113,44 @@ mod feat_ssr {
                 VTagInner::Other {
                     ref tag,
                     ref children,
-                    ..
+                    ref text,
                 } => {
-                    children.render_to_string(w, parent_scope).await;
+                    match text {
+                        Some(text) => VText::new(text.clone()).render_to_string(w).await,
+                        // Whitespace is significant in the content of these elements, so they are
+                        // never pretty printed
+                        None if options.pretty
+                            && !matches!(&**tag, "pre" | "textarea" | "script" | "style") =>
+                        {
+                            let nested = RenderOptions {
+                                depth: options.depth + 1,
+                                ..options
+                            };
+                            let mut inner = String::new();
+                            children
+                                .render_to_string(&mut inner, parent_scope, nested)
+                                .await?;
+
+                            if !inner.is_empty() {
+                                nested.write_line_break(w);
+                                w.push_str(&inner);
+                                options.write_line_break(w);
+                            }
+                        }
+                        None => {
+                            let options = RenderOptions {
+                                pretty: false,
+                                ..options
+                            };
+                            children.render_to_string(w, parent_scope, options).await?;
+                        }
+                    }
 
                     write!(w, "</{}>", tag).unwrap();
                 }
             }
+
+            Ok(())
         }
     }
 }
@@ -707,7 +1158,7 @@ mod feat_ssr {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{html, Html};
+    use crate::{classes, html, Html};
 
     #[cfg(feature = "wasm_test")]
     use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
@@ -719,6 +1170,138 @@ mod tests {
         AnyScope::test()
     }
 
+    #[test]
+    fn on_mount_and_on_unmount() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+
+        let list = |keys: &[&'static str]| -> VNode {
+            keys.iter()
+                .map(|&key| {
+                    let mut tag = VTag::new("i");
+                    tag.key = Some(key.into());
+                    tag.add_attribute("id", key);
+                    let mounted = events.clone();
+                    tag.set_on_mount(move |el| mounted.borrow_mut().push(format!("+{}", el.id())));
+                    let unmounted = events.clone();
+                    tag.set_on_unmount(move |el| {
+                        // Still in the document
+                        assert!(el.parent_node().is_some());
+                        unmounted.borrow_mut().push(format!("-{}", el.id()));
+                    });
+                    VNode::from(tag)
+                })
+                .collect()
+        };
+
+        let mut ancestor = list(&["a", "b", "c"]);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(*events.borrow(), ["+a", "+b", "+c"]);
+
+        // Reordering and patching keeps the elements mounted
+        let mut node = list(&["c", "a", "b"]);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(*events.borrow(), ["+a", "+b", "+c"]);
+        assert_eq!(
+            parent.inner_html(),
+            r#"<i id="c"></i><i id="a"></i><i id="b"></i>"#
+        );
+
+        ancestor = node;
+        let mut node = list(&["a", "d"]);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        events.borrow_mut().sort();
+        assert_eq!(*events.borrow(), ["+a", "+b", "+c", "+d", "-b", "-c"]);
+        events.borrow_mut().clear();
+
+        node.detach(&parent, false);
+        events.borrow_mut().sort();
+        assert_eq!(*events.borrow(), ["-a", "-d"]);
+        assert_eq!(parent.inner_html(), "");
+    }
+
+    #[test]
+    fn typed_enumerated_attributes() {
+        use crate::html::{AutoCapitalize, EnterKeyHint, InputMode};
+
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        let tag = |mode: InputMode, hint: Option<EnterKeyHint>| -> VNode {
+            html! {
+                <input inputmode={mode} autocapitalize={AutoCapitalize::Words} enterkeyhint={hint} />
+            }
+        };
+        let attrs = |el: &Element| -> Vec<Option<String>> {
+            ["inputmode", "autocapitalize", "enterkeyhint"]
+                .iter()
+                .map(|name| el.get_attribute(name))
+                .collect()
+        };
+        let some = |v: &str| Some(v.to_string());
+
+        let mut ancestor = tag(InputMode::Numeric, Some(EnterKeyHint::Next));
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let el = parent.first_element_child().unwrap();
+        assert_eq!(attrs(&el), [some("numeric"), some("words"), some("next")]);
+
+        let mut node = tag(InputMode::Email, None);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(attrs(&el), [some("email"), some("words"), None]);
+    }
+
+    #[test]
+    fn batched_attribute_changes() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        let tag = |id: &'static str,
+                   title: Option<&'static str>,
+                   label: Option<&'static str>,
+                   class: Option<&'static str>|
+         -> VNode {
+            html! { <div id={id} title={title} aria-label={label} class={class} /> }
+        };
+        let attrs = |el: &Element| -> Vec<Option<String>> {
+            ["id", "title", "aria-label", "class"]
+                .iter()
+                .map(|name| el.get_attribute(name))
+                .collect()
+        };
+        let some = |v: &str| Some(v.to_string());
+
+        let mut ancestor = tag("a", Some("title"), None, Some("x y"));
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let el = parent.first_element_child().unwrap();
+        assert_eq!(attrs(&el), [some("a"), some("title"), None, some("x y")]);
+
+        // Change, remove, add and patch classes in one go
+        let mut node = tag("b", None, Some("label"), Some("y z"));
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.first_element_child().unwrap(), el);
+        assert_eq!(attrs(&el), [some("b"), None, some("label"), some("y z")]);
+
+        // Classes added outside of Yew keep the class attribute around
+        el.class_list().add_1("external").unwrap();
+        ancestor = node;
+        let mut node = tag("c", Some("title"), None, None);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(
+            attrs(&el),
+            [some("c"), some("title"), None, some("external")]
+        );
+
+        el.class_list().remove_1("external").unwrap();
+        ancestor = node;
+        let mut node = tag("c", Some("title"), None, Some("x"));
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        ancestor = node;
+        let mut node = tag("c", None, None, None);
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(attrs(&el), [some("c"), None, None, None]);
+    }
+
     #[test]
     fn it_compares_tags() {
         let a = html! {
@@ -911,6 +1494,52 @@ mod tests {
         assert_namespace(g_tag, SVG_NAMESPACE);
     }
 
+    #[test]
+    fn fragment_siblings_choose_their_own_namespace() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        let mut node = html! {
+            <>
+                <svg><circle /></svg>
+                <div><span /></div>
+                <math><mi /></math>
+                <svg><foreignObject><p /></foreignObject></svg>
+                <custom xmlns="urn:custom"><item /></custom>
+            </>
+        };
+        node.apply(&scope, &parent, NodeRef::default(), None);
+
+        // Elements in document order. The macro lowercases tag names, hence `foreignobject`.
+        fn collect(el: &Element, out: &mut Vec<(String, String)>) {
+            let mut child = el.first_element_child();
+            while let Some(el) = child {
+                out.push((el.local_name(), el.namespace_uri().unwrap()));
+                collect(&el, out);
+                child = el.next_element_sibling();
+            }
+        }
+        let mut namespaces = Vec::new();
+        collect(&parent, &mut namespaces);
+        let expected: Vec<_> = [
+            ("svg", SVG_NAMESPACE),
+            ("circle", SVG_NAMESPACE),
+            ("div", HTML_NAMESPACE),
+            ("span", HTML_NAMESPACE),
+            ("math", MATHML_NAMESPACE),
+            ("mi", MATHML_NAMESPACE),
+            ("svg", SVG_NAMESPACE),
+            ("foreignobject", SVG_NAMESPACE),
+            ("p", HTML_NAMESPACE),
+            ("custom", "urn:custom"),
+            ("item", "urn:custom"),
+        ]
+        .iter()
+        .map(|(tag, ns)| (tag.to_string(), ns.to_string()))
+        .collect();
+        assert_eq!(namespaces, expected);
+    }
+
     #[test]
     fn it_compares_values() {
         let a = html! {
@@ -1038,6 +1667,44 @@ mod tests {
         test_set_class_name(|| html! { <div class={"ferris the crab".to_owned()}></div> });
     }
 
+    #[test]
+    fn text_content_mode() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        let text_mode = |count: u32| {
+            let mut tag = VTag::new("span");
+            tag.set_text_content(count.to_string());
+            VNode::VTag(Box::new(tag))
+        };
+        let child_mode = |count: u32| html! { <span>{ count }</span> };
+
+        let mut expected = document().create_element("div").unwrap();
+        let mut node = child_mode(1);
+        node.apply(&scope, &expected, NodeRef::default(), None);
+        let mut ancestor = text_mode(1);
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        assert_eq!(parent.inner_html(), expected.inner_html());
+        let el = parent.first_child().unwrap();
+
+        // Switch between both modes, while reusing the element
+        for (count, text) in [(2, true), (3, false), (4, false), (5, true), (5, true)] {
+            let mut node = if text {
+                text_mode(count)
+            } else {
+                child_mode(count)
+            };
+            node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+            ancestor = node;
+
+            expected = document().create_element("div").unwrap();
+            child_mode(count).apply(&scope, &expected, NodeRef::default(), None);
+            assert_eq!(parent.inner_html(), expected.inner_html());
+            assert_eq!(parent.first_child(), Some(el.clone()));
+            assert_eq!(el.child_nodes().length(), 1);
+        }
+    }
+
     #[test]
     fn controlled_input_synced() {
         let scope = test_scope();
@@ -1083,6 +1750,130 @@ mod tests {
         assert_eq!(current_value, expected);
     }
 
+    #[test]
+    fn class_patched_by_token() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let classes = |active: bool| {
+            html! { <div class={classes!("item", active.then(|| "active"))}></div> }
+        };
+
+        let mut elem = classes(false);
+        VDiff::apply(&mut elem, &scope, &parent, NodeRef::default(), None);
+        let ancestor = if let VNode::VTag(vtag) = elem {
+            vtag
+        } else {
+            panic!("should be vtag")
+        };
+
+        // A class added outside of Yew, e.g. by an animation library
+        let el = ancestor.reference.clone().unwrap();
+        el.class_list().add_1("fade-in").unwrap();
+
+        let mut elem = classes(true);
+        let vtag = assert_vtag_mut(&mut elem);
+        vtag.apply(
+            &scope,
+            &parent,
+            NodeRef::default(),
+            Some(VNode::VTag(ancestor)),
+        );
+        assert_eq!(el.get_attribute("class").unwrap(), "item fade-in active");
+
+        let ancestor = elem;
+        let mut elem = classes(false);
+        VDiff::apply(
+            &mut elem,
+            &scope,
+            &parent,
+            NodeRef::default(),
+            Some(ancestor),
+        );
+        assert_eq!(el.get_attribute("class").unwrap(), "item fade-in");
+
+        parent.remove();
+    }
+
+    #[test]
+    fn controlled_checkbox_synced() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let mut elem = html! { <input type="checkbox" checked=true /> };
+        VDiff::apply(&mut elem, &scope, &parent, NodeRef::default(), None);
+        let ancestor = if let VNode::VTag(vtag) = elem {
+            vtag
+        } else {
+            panic!("should be vtag")
+        };
+
+        // User unchecks the box
+        let input = ancestor.reference.as_ref().unwrap().clone();
+        input.unchecked_ref::<InputElement>().click();
+        assert!(!input.unchecked_ref::<InputElement>().checked());
+
+        // Rendering the unchanged state forces the property back
+        let mut elem = html! { <input type="checkbox" checked=true /> };
+        let vtag = assert_vtag_mut(&mut elem);
+        vtag.apply(
+            &scope,
+            &parent,
+            NodeRef::default(),
+            Some(VNode::VTag(ancestor)),
+        );
+
+        let input = vtag.reference.as_ref().unwrap();
+        assert!(input.unchecked_ref::<InputElement>().checked());
+        // Only the property is controlled, the attribute is left alone
+        assert!(!input.has_attribute("checked"));
+
+        parent.remove();
+    }
+
+    #[test]
+    fn controlled_textarea_synced() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        let mut elem = html! { <textarea value="controlled" /> };
+        VDiff::apply(&mut elem, &scope, &parent, NodeRef::default(), None);
+        let ancestor = if let VNode::VTag(vtag) = elem {
+            vtag
+        } else {
+            panic!("should be vtag")
+        };
+
+        // User input
+        let textarea = ancestor.reference.as_ref().unwrap().clone();
+        textarea
+            .unchecked_ref::<TextAreaElement>()
+            .set_value("User input");
+
+        let mut elem = html! { <textarea value="controlled" /> };
+        let vtag = assert_vtag_mut(&mut elem);
+        vtag.apply(
+            &scope,
+            &parent,
+            NodeRef::default(),
+            Some(VNode::VTag(ancestor)),
+        );
+
+        let textarea = vtag.reference.as_ref().unwrap();
+        assert_eq!(
+            textarea.unchecked_ref::<TextAreaElement>().value(),
+            "controlled"
+        );
+
+        parent.remove();
+    }
+
     #[test]
     fn uncontrolled_input_unsynced() {
         let scope = test_scope();
@@ -1260,14 +2051,86 @@ mod tests {
             "<div id=\"after\"></div>"
         );
     }
+
+    #[test]
+    fn pooled_elements_are_reused() {
+        let scope = test_scope();
+        let parent = document().create_element("div").unwrap();
+        document().body().unwrap().append_child(&parent).unwrap();
+
+        set_element_pooling(true);
+
+        let mut before = html! {
+            <>
+                <p id="a">{"a"}</p>
+                <p id="b">{"b"}</p>
+            </>
+        };
+        before.apply(&scope, &parent, NodeRef::default(), None);
+        let removed = parent.last_child().unwrap();
+
+        // Scroll the second item out of view
+        let mut middle = html! {
+            <>
+                <p id="a">{"a"}</p>
+            </>
+        };
+        middle.apply(&scope, &parent, NodeRef::default(), Some(before));
+        assert_eq!(parent.child_nodes().length(), 1);
+
+        // And a new one into view
+        let mut after = html! {
+            <>
+                <p id="a">{"a"}</p>
+                <p class="c">{"c"}</p>
+            </>
+        };
+        after.apply(&scope, &parent, NodeRef::default(), Some(middle));
+
+        let added = parent.last_child().unwrap();
+        set_element_pooling(false);
+
+        assert_eq!(
+            added, removed,
+            "element should have been taken from the pool"
+        );
+        assert_eq!(
+            added.dyn_ref::<Element>().unwrap().outer_html(),
+            "<p class=\"c\">c</p>"
+        );
+    }
+
+    #[test]
+    fn only_plain_elements_are_pooled() {
+        set_element_pooling(true);
+
+        recycle_element(document().create_element("span").unwrap());
+        recycle_element(document().create_element("video").unwrap());
+        recycle_element(document().create_element("my-element").unwrap());
+        let host = document().create_element("div").unwrap();
+        host.attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+            .unwrap();
+        recycle_element(host);
+
+        let span = take_pooled_element(HTML_NAMESPACE, "span");
+        let video = take_pooled_element(HTML_NAMESPACE, "video");
+        let custom = take_pooled_element(HTML_NAMESPACE, "my-element");
+        let host = take_pooled_element(HTML_NAMESPACE, "div");
+        set_element_pooling(false);
+
+        assert!(span.is_some());
+        assert!(video.is_none(), "media elements should not be pooled");
+        assert!(custom.is_none(), "custom elements should not be pooled");
+        assert!(host.is_none(), "shadow hosts should not be pooled");
+    }
 }
 
 #[cfg(test)]
 mod layout_tests {
     extern crate self as yew;
 
-    use crate::html;
     use crate::tests::layout_tests::{diff_layouts, TestLayout};
+    use crate::{classes, html};
 
     #[cfg(feature = "wasm_test")]
     use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
@@ -1275,6 +2138,21 @@ mod layout_tests {
     #[cfg(feature = "wasm_test")]
     wasm_bindgen_test_configure!(run_in_browser);
 
+    #[test]
+    fn conditional_classes() {
+        let layout = |active: bool| TestLayout {
+            name: if active { "active" } else { "inactive" },
+            node: html! { <button class={classes!("btn", ("active", active))} /> },
+            expected: if active {
+                r#"<button class="btn active"></button>"#
+            } else {
+                r#"<button class="btn"></button>"#
+            },
+        };
+
+        diff_layouts(vec![layout(false), layout(true), layout(false)]);
+    }
+
     #[test]
     fn diff() {
         let layout1 = TestLayout {
@@ -1362,6 +2240,22 @@ mod layout_tests {
 mod tests_without_browser {
     use crate::html;
 
+    #[test]
+    fn attribute_shorthand() {
+        let value = "hello";
+        let name = String::from("greeting");
+        let checked = true;
+
+        assert_eq!(
+            html! { <input {value} {name} {checked} /> },
+            html! { <input value={value} name={name.clone()} checked={checked} /> },
+        );
+        assert_ne!(
+            html! { <input {value} /> },
+            html! { <input name={value} /> },
+        );
+    }
+
     #[test]
     fn html_if_bool() {
         assert_eq!(
@@ -1505,6 +2399,38 @@ mod tests_without_browser {
             html! { <div><></></div> },
         );
     }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn source_location_is_captured() {
+        use crate::virtual_dom::VNode;
+
+        let node = html! {
+            <div>
+                <><span /></>
+            </div>
+        };
+        let line = line!();
+
+        let div = match &node {
+            VNode::VTag(vtag) => vtag,
+            _ => panic!("expected a VTag"),
+        };
+        let location = node.source_location().unwrap();
+        assert_eq!(location.file(), file!());
+        assert_eq!(location.line(), line - 4);
+
+        let fragment = &div.children()[0];
+        assert_eq!(fragment.source_location().unwrap().line(), line - 3);
+        match fragment {
+            VNode::VList(vlist) => {
+                assert_eq!(vlist[0].source_location().unwrap().line(), line - 3);
+            }
+            _ => panic!("expected a VList"),
+        }
+
+        assert_eq!(html! { {"text"} }.source_location(), None);
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32"), feature = "ssr"))]
@@ -1583,4 +2509,47 @@ mod ssr_tests {
 
         assert_eq!(s, r#"<textarea>teststring</textarea>"#);
     }
+
+    #[test]
+    async fn test_text_content() {
+        use crate::virtual_dom::{VNode, VTag};
+
+        #[function_component]
+        fn Comp() -> Html {
+            let mut tag = VTag::new("span");
+            tag.set_text_content("<b>1</b>");
+            VNode::VTag(Box::new(tag))
+        }
+
+        let renderer = ServerRenderer::<Comp>::new();
+
+        let s = renderer.render().await;
+
+        assert_eq!(s, r#"<span>&lt;b&gt;1&lt;/b&gt;</span>"#);
+    }
+
+    #[test]
+    async fn test_optional_attributes() {
+        #[function_component]
+        fn Comp() -> Html {
+            use crate::virtual_dom::AttrValue;
+
+            let label: Option<AttrValue> = Some("greeting".into());
+            html! {
+                <div
+                    title={None::<&str>}
+                    aria-label={label}
+                    data-id={None::<String>}
+                    hidden={Some(true)}
+                    disabled={None::<bool>}
+                />
+            }
+        }
+
+        let renderer = ServerRenderer::<Comp>::new();
+
+        let s = renderer.render().await;
+
+        assert_eq!(s, r#"<div aria-label="greeting" hidden="hidden"></div>"#);
+    }
 }
@@ -2,10 +2,9 @@
 
 use super::{AttrValue, VDiff, VNode};
 use crate::html::{AnyScope, NodeRef};
-use gloo::console;
 use gloo_utils::document;
 use std::cmp::PartialEq;
-use web_sys::{Element, Text as TextNode};
+use web_sys::{Node, Text as TextNode};
 
 /// A type for a virtual
 /// [`TextNode`](https://developer.mozilla.org/en-US/docs/Web/API/Document/createTextNode)
@@ -53,23 +52,24 @@ impl std::fmt::Debug for VText {
     }
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Number of times the content of a rendered text node was rewritten
+    static TEXT_WRITES: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
 impl VDiff for VText {
     /// Remove VText from parent.
-    fn detach(&mut self, parent: &Element, parent_to_detach: bool) {
-        let node = self
-            .reference
-            .take()
-            .expect("tried to remove not rendered VText from DOM");
-        if !parent_to_detach {
-            let result = parent.remove_child(&node);
-
-            if result.is_err() {
-                console::warn!("Node not found to remove VText");
+    fn detach(&mut self, parent: &Node, parent_to_detach: bool) {
+        // The node might have been detached already
+        if let Some(node) = self.reference.take() {
+            if !parent_to_detach {
+                super::remove_node(&node, parent);
             }
         }
     }
 
-    fn shift(&self, previous_parent: &Element, next_parent: &Element, next_sibling: NodeRef) {
+    fn shift(&self, previous_parent: &Node, next_parent: &Node, next_sibling: NodeRef) {
         let node = self
             .reference
             .as_ref()
@@ -85,7 +85,7 @@ impl VDiff for VText {
     fn apply(
         &mut self,
         _parent_scope: &AnyScope,
-        parent: &Element,
+        parent: &Node,
         next_sibling: NodeRef,
         ancestor: Option<VNode>,
     ) -> NodeRef {
@@ -97,6 +97,8 @@ impl VDiff for VText {
                     .clone()
                     .expect("Rendered VText nodes should have a ref");
                 if self.text != vtext.text {
+                    #[cfg(test)]
+                    TEXT_WRITES.with(|c| c.set(c.get() + 1));
                     text_node.set_node_value(Some(&self.text));
                 }
 
@@ -141,6 +143,70 @@ mod test {
             { "Text Node As Root" }
         };
     }
+
+    #[test]
+    fn shared_text_skips_rewrite() {
+        use super::TEXT_WRITES;
+        use crate::html::{AnyScope, NodeRef};
+        use crate::virtual_dom::{AttrValue, VDiff, VNode, VText};
+        use gloo_utils::document;
+        use std::rc::Rc;
+
+        let label: Rc<str> = Rc::from("label");
+        assert!(AttrValue::Rc(label.clone()).ptr_eq(&AttrValue::Rc(label.clone())));
+        assert!(!AttrValue::Rc(label.clone()).ptr_eq(&AttrValue::Rc(Rc::from("label"))));
+
+        let scope = AnyScope::test();
+        let parent = document().create_element("div").unwrap();
+
+        let mut ancestor = VNode::VText(VText::new(label.clone()));
+        ancestor.apply(&scope, &parent, NodeRef::default(), None);
+        let text_node = parent.first_child().unwrap();
+
+        let writes = TEXT_WRITES.with(|c| c.get());
+        let mut node = VNode::VText(VText::new(label));
+        node.apply(&scope, &parent, NodeRef::default(), Some(ancestor));
+        assert_eq!(parent.first_child(), Some(text_node));
+        assert_eq!(TEXT_WRITES.with(|c| c.get()), writes);
+
+        // Changed text is still written
+        let mut changed = VNode::VText(VText::new(Rc::<str>::from("changed")));
+        changed.apply(&scope, &parent, NodeRef::default(), Some(node));
+        assert_eq!(TEXT_WRITES.with(|c| c.get()), writes + 1);
+        assert_eq!(parent.inner_html(), "changed");
+    }
+
+    #[test]
+    fn coalesced_whitespace_is_a_single_text_node() {
+        use crate::virtual_dom::VNode;
+
+        let text_children = |node: VNode| match node {
+            VNode::VList(list) => list
+                .iter()
+                .map(|child| match child {
+                    VNode::VText(text) => Some(text.text.to_string()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            _ => panic!("expected a fragment"),
+        };
+
+        assert_eq!(
+            text_children(html! { <>{"a"}{" "}{"b"}<b></b>{" "}{'c'}</> }),
+            vec![
+                Some("a".to_string()),
+                Some(" ".to_string()),
+                Some("b".to_string()),
+                None,
+                Some(" ".to_string()),
+                Some("c".to_string()),
+            ]
+        );
+        assert_eq!(
+            text_children(html! { <whitespace="coalesce">{"a"}{" "}{"b"}<b></b>{" "}{'c'}</> }),
+            vec![Some("a b".to_string()), None, Some(" c".to_string())]
+        );
+    }
 }
 
 #[cfg(test)]
@@ -194,6 +260,41 @@ mod layout_tests {
 
         diff_layouts(vec![layout1, layout2, layout3, layout4]);
     }
+
+    #[test]
+    fn whitespace() {
+        let layout1 = TestLayout {
+            name: "whitespace between literals is not significant",
+            node: html! { <>{"a"}   {"b"}</> },
+            expected: "ab",
+        };
+
+        let layout2 = TestLayout {
+            name: "whitespace literals are preserved",
+            node: html! { <>{"a"}{" "}{"b"}</> },
+            expected: "a b",
+        };
+
+        let layout3 = TestLayout {
+            name: "whitespace around elements is preserved",
+            node: html! { <>{"a "}<b>{" b "}</b>{" c"}</> },
+            expected: "a <b> b </b> c",
+        };
+
+        let layout4 = TestLayout {
+            name: "whitespace-only text next to elements",
+            node: html! { <><i></i>{" "}<b></b>{"  "}</> },
+            expected: "<i></i> <b></b>  ",
+        };
+
+        let layout5 = TestLayout {
+            name: "coalesced whitespace literals",
+            node: html! { <whitespace="coalesce">{"a"}{" "}{"b"}<b>{"c"}</b>{" "}{1}</> },
+            expected: "a b<b>c</b> 1",
+        };
+
+        diff_layouts(vec![layout1, layout2, layout3, layout4, layout5]);
+    }
 }
 
 #[cfg(all(test, not(target_arch = "wasm32"), feature = "ssr"))]
@@ -0,0 +1,35 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gloo::timers::future::sleep;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn into_js_fires_from_js_until_dropped() {
+    let fired = Rc::new(Cell::new(0));
+    let callback = {
+        let fired = fired.clone();
+        Callback::from(move |_: JsValue| fired.set(fired.get() + 1))
+    };
+
+    let js = callback.into_js();
+    gloo_utils::window()
+        .set_timeout_with_callback_and_timeout_and_arguments_0(&js, 10)
+        .unwrap();
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(fired.get(), 1);
+
+    let function = js_sys::Function::clone(&js);
+    function.call1(&JsValue::NULL, &JsValue::NULL).unwrap();
+    assert_eq!(fired.get(), 2);
+
+    // The closure is released along with the handle
+    drop(js);
+    assert!(function.call1(&JsValue::NULL, &JsValue::NULL).is_err());
+    assert_eq!(fired.get(), 2);
+}
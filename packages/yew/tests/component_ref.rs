@@ -0,0 +1,89 @@
+mod common;
+
+use common::obtain_result;
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+use yew::html::ComponentRef;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+struct Counter {
+    count: usize,
+}
+
+enum CounterMsg {
+    Increment,
+}
+
+impl Component for Counter {
+    type Message = CounterMsg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { count: 0 }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            CounterMsg::Increment => self.count += 1,
+        }
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! { <span>{ self.count }</span> }
+    }
+}
+
+struct App {
+    counter: ComponentRef<Counter>,
+}
+
+impl Component for App {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            counter: ComponentRef::default(),
+        }
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let onclick = {
+            let counter = self.counter.clone();
+            Callback::from(move |_| {
+                assert!(counter.send_message(CounterMsg::Increment));
+            })
+        };
+        html! {
+            <div id="result">
+                <Counter ref={self.counter.clone()} />
+                <button {onclick} />
+            </div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn parent_triggers_child_through_ref() {
+    yew::start_app_in_element::<App>(gloo_utils::document().get_element_by_id("output").unwrap());
+    sleep(Duration::ZERO).await;
+    assert_eq!(obtain_result(), "<span>0</span><button></button>");
+
+    let button = gloo_utils::document()
+        .query_selector("#result button")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap();
+    button.click();
+    button.click();
+    sleep(Duration::ZERO).await;
+
+    assert_eq!(obtain_result(), "<span>2</span><button></button>");
+}
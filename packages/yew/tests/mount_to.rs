@@ -0,0 +1,38 @@
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Properties, PartialEq)]
+struct Props {
+    text: String,
+}
+
+#[function_component]
+fn Greeting(props: &Props) -> Html {
+    html! { <p>{ &props.text }</p> }
+}
+
+#[wasm_bindgen_test]
+async fn mounts_to_detached_element() {
+    let element = gloo_utils::document().create_element("div").unwrap();
+    let node_ref = NodeRef::default();
+    node_ref.set(Some(element.clone().into()));
+
+    let app = yew::mount_to_with_props::<Greeting>(
+        &node_ref,
+        Props {
+            text: "hello".into(),
+        },
+    );
+    sleep(Duration::ZERO).await;
+
+    assert!(element.parent_node().is_none());
+    assert_eq!(element.inner_html(), "<p>hello</p>");
+
+    app.destroy();
+    sleep(Duration::ZERO).await;
+    assert_eq!(element.inner_html(), "");
+}
@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+use common::obtain_result;
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+static NODE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Properties, PartialEq)]
+struct RowsProps {
+    rows: usize,
+}
+
+struct Rows;
+
+impl Component for Rows {
+    type Message = ();
+    type Properties = RowsProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                { for (0..ctx.props().rows).map(|i| html! { <div class="row">{ i }</div> }) }
+            </>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        NODE_COUNT.store(ctx.link().node_count().unwrap(), Ordering::Relaxed);
+    }
+}
+
+struct App {
+    rows: usize,
+}
+
+impl Component for App {
+    type Message = usize;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { rows: 3 }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        self.rows = msg;
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <div id="result">
+                <Rows rows={self.rows} />
+                <button />
+            </div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn reports_rendered_node_count() {
+    let app = yew::start_app_in_element::<App>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    assert_eq!(
+        obtain_result(),
+        r#"<div class="row">0</div><div class="row">1</div><div class="row">2</div><button></button>"#
+    );
+    assert_eq!(NODE_COUNT.load(Ordering::Relaxed), 3);
+
+    app.send_message(5);
+    sleep(Duration::ZERO).await;
+    assert_eq!(NODE_COUNT.load(Ordering::Relaxed), 5);
+
+    // The placeholder of the empty fragment
+    app.send_message(0);
+    sleep(Duration::ZERO).await;
+    assert_eq!(NODE_COUNT.load(Ordering::Relaxed), 1);
+}
+
+struct Outer;
+
+impl Component for Outer {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <div id="result">
+                <Rows rows={2} />
+                <p />
+            </div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn node_count_is_readable_outside_of_rendered() {
+    let app = yew::start_app_in_element::<Outer>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+
+    // A single element at the root of the component, with the nodes of `Rows` below it
+    assert_eq!(app.node_count(), Some(1));
+    assert_eq!(
+        app.root_node().map(|node| node.node_name()),
+        Some("DIV".to_owned())
+    );
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+use yew::progressive::ProgressiveList;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+const ITEMS: usize = 250;
+
+fn items() -> impl Iterator<Item = Html> {
+    (0..ITEMS).map(|i| html! { <li key={i}>{ i }</li> })
+}
+
+#[wasm_bindgen_test]
+async fn progressive_list_grows_to_full_list() {
+    #[function_component(Progressive)]
+    fn progressive() -> Html {
+        html! {
+            <ul id="result">
+                <ProgressiveList chunk_size={100}>
+                    { for items() }
+                </ProgressiveList>
+            </ul>
+        }
+    }
+
+    #[function_component(OneShot)]
+    fn one_shot() -> Html {
+        html! {
+            <ul id="result">
+                { for items() }
+            </ul>
+        }
+    }
+
+    let output = gloo_utils::document().get_element_by_id("output").unwrap();
+    let result = || {
+        gloo_utils::document()
+            .get_element_by_id("result")
+            .expect("No result found. Most likely, the application crashed and burned")
+    };
+
+    let handle = yew::start_app_in_element::<OneShot>(output.clone());
+    sleep(Duration::ZERO).await;
+    let expected = result().inner_html();
+    handle.destroy();
+
+    let _handle = yew::start_app_in_element::<Progressive>(output);
+    sleep(Duration::ZERO).await;
+    assert_eq!(result().child_element_count(), 100);
+
+    let mut counts = vec![];
+    for _ in 0..50 {
+        let count = result().child_element_count() as usize;
+        if counts.last() != Some(&count) {
+            counts.push(count);
+        }
+        if count == ITEMS {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+
+    // The list grew over several frames
+    assert_eq!(counts, vec![100, 200, ITEMS]);
+    assert_eq!(result().inner_html(), expected);
+}
+
+#[wasm_bindgen_test]
+async fn progressive_list_slices_updates() {
+    const ROWS: usize = 10_000;
+
+    struct Rows {
+        prefix: char,
+    }
+
+    impl Component for Rows {
+        type Message = char;
+        type Properties = ();
+
+        fn create(_ctx: &Context<Self>) -> Self {
+            Self { prefix: 'a' }
+        }
+
+        fn update(&mut self, _ctx: &Context<Self>, prefix: Self::Message) -> bool {
+            self.prefix = prefix;
+            true
+        }
+
+        fn view(&self, _ctx: &Context<Self>) -> Html {
+            let prefix = self.prefix;
+            html! {
+                <ul id="result">
+                    <ProgressiveList chunk_size={2500} slice_updates=true>
+                        { for (0..ROWS).map(|i| {
+                            let row = format!("{}{}", prefix, i);
+                            html! { <li key={row.clone()}>{ row }</li> }
+                        }) }
+                    </ProgressiveList>
+                </ul>
+            }
+        }
+    }
+
+    let result = || {
+        gloo_utils::document()
+            .get_element_by_id("result")
+            .expect("No result found. Most likely, the application crashed and burned")
+    };
+    // Number of rows with the prefix, asserting the rows are in order
+    let rows_with = |prefix: char| {
+        let rows = result().children();
+        assert_eq!(rows.length() as usize, ROWS);
+        (0..ROWS)
+            .filter(|&i| {
+                let text = rows.item(i as u32).unwrap().text_content().unwrap();
+                assert_eq!(&text[1..], i.to_string());
+                text.starts_with(prefix)
+            })
+            .count()
+    };
+
+    let handle = yew::start_app_in_element::<Rows>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    for _ in 0..200 {
+        if result().child_element_count() as usize == ROWS {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(rows_with('a'), ROWS);
+
+    // The rows are swapped across several frames
+    handle.send_message('b');
+    sleep(Duration::ZERO).await;
+    let swapped = rows_with('b');
+    assert!(swapped < ROWS, "all rows were swapped at once");
+
+    // Changing the rows again mid-slice restarts from what is shown
+    handle.send_message('c');
+    let mut counts = vec![];
+    for _ in 0..200 {
+        let count = rows_with('c');
+        if counts.last() != Some(&count) {
+            counts.push(count);
+        }
+        if count == ROWS {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert!(
+        counts.len() > 2,
+        "rows were not swapped in chunks: {:?}",
+        counts
+    );
+    assert_eq!(counts.last(), Some(&ROWS));
+    handle.destroy();
+}
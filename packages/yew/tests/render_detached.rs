@@ -0,0 +1,27 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn render_detached_measures_content() {
+    let (container, cleanup) = yew::render_detached(html! {
+        <div id="tooltip" style="width: 120px; height: 30px;">{ "tooltip" }</div>
+    });
+
+    let tooltip = gloo_utils::document()
+        .get_element_by_id("tooltip")
+        .unwrap()
+        .dyn_into::<web_sys::HtmlElement>()
+        .unwrap();
+    assert_eq!(tooltip.parent_element(), Some(container.clone()));
+    assert_eq!(tooltip.offset_width(), 120);
+    assert_eq!(tooltip.offset_height(), 30);
+
+    cleanup();
+    assert!(gloo_utils::document()
+        .get_element_by_id("tooltip")
+        .is_none());
+    assert!(container.parent_node().is_none());
+}
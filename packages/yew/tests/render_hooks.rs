@@ -0,0 +1,141 @@
+#![cfg(feature = "render_hooks")]
+
+mod common;
+
+use common::obtain_result;
+use gloo::timers::future::sleep;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+struct Counter {
+    count: usize,
+}
+
+impl Component for Counter {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { count: 0 }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
+        self.count += 1;
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <div id="result">{ self.count }</div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn hooks_fire_around_rerender() {
+    let app = yew::start_app_in_element::<Counter>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    assert_eq!(obtain_result(), "0");
+
+    // Records the rendered content seen by each hook
+    let events = Rc::new(RefCell::new(Vec::new()));
+    app.on_render_start({
+        let events = events.clone();
+        Callback::from(move |_| {
+            events
+                .borrow_mut()
+                .push(format!("start {}", obtain_result()))
+        })
+    });
+    app.on_render_commit({
+        let events = events.clone();
+        Callback::from(move |_| {
+            events
+                .borrow_mut()
+                .push(format!("commit {}", obtain_result()))
+        })
+    });
+
+    app.send_message(());
+    sleep(Duration::ZERO).await;
+    assert_eq!(*events.borrow(), ["start 0", "commit 1"]);
+
+    app.destroy();
+}
+
+#[derive(Properties, PartialEq)]
+struct ChildProps {
+    count: usize,
+}
+
+#[function_component]
+fn Child(props: &ChildProps) -> Html {
+    html! {
+        <div id="result">{ props.count }</div>
+    }
+}
+
+struct Parent {
+    count: usize,
+}
+
+impl Component for Parent {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { count: 0 }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
+        self.count += 1;
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <Child count={self.count} />
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn hooks_fire_once_for_nested_rerenders() {
+    let app = yew::start_app_in_element::<Parent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    assert_eq!(obtain_result(), "0");
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    app.on_render_start({
+        let events = events.clone();
+        Callback::from(move |_| {
+            events
+                .borrow_mut()
+                .push(format!("start {}", obtain_result()))
+        })
+    });
+    app.on_render_commit({
+        let events = events.clone();
+        Callback::from(move |_| {
+            events
+                .borrow_mut()
+                .push(format!("commit {}", obtain_result()))
+        })
+    });
+
+    // The parent and the child render in the same batch
+    app.send_message(());
+    sleep(Duration::ZERO).await;
+    assert_eq!(*events.borrow(), ["start 0", "commit 1"]);
+
+    app.destroy();
+}
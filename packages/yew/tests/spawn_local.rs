@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+use common::obtain_result;
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+static FINISHED: AtomicUsize = AtomicUsize::new(0);
+
+struct Fetching;
+
+impl Component for Fetching {
+    type Message = ();
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().spawn_local(async {
+            STARTED.fetch_add(1, Ordering::Relaxed);
+            sleep(Duration::from_millis(50)).await;
+            FINISHED.fetch_add(1, Ordering::Relaxed);
+        });
+
+        Self
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! { <div id="result">{ "fetching" }</div> }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn spawn_local_aborts_on_destroy() {
+    STARTED.store(0, Ordering::Relaxed);
+    FINISHED.store(0, Ordering::Relaxed);
+
+    // Runs to completion while the component is mounted
+    let app = yew::start_app_in_element::<Fetching>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(obtain_result(), "fetching");
+    assert_eq!(STARTED.load(Ordering::Relaxed), 1);
+    assert_eq!(FINISHED.load(Ordering::Relaxed), 1);
+    app.destroy();
+
+    // The continuation does not run after the component was destroyed
+    let app = yew::start_app_in_element::<Fetching>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    assert_eq!(STARTED.load(Ordering::Relaxed), 2);
+    app.destroy();
+
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(FINISHED.load(Ordering::Relaxed), 1);
+}
+
+static POLLED: AtomicUsize = AtomicUsize::new(0);
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+struct DropGuard;
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Waiting;
+
+impl Component for Waiting {
+    type Message = ();
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let guard = DropGuard;
+        ctx.link().spawn_local(async move {
+            let _guard = guard;
+            POLLED.fetch_add(1, Ordering::Relaxed);
+            sleep(Duration::from_millis(50)).await;
+        });
+
+        Self
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! { <div id="result">{ "waiting" }</div> }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn spawn_local_aborts_before_first_poll() {
+    POLLED.store(0, Ordering::Relaxed);
+    DROPPED.store(0, Ordering::Relaxed);
+
+    // Destroyed before the task was polled for the first time
+    let app = yew::start_app_in_element::<Waiting>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    app.destroy();
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(POLLED.load(Ordering::Relaxed), 0);
+
+    // Dropped on destruction, without waiting for the task to be woken
+    let app = yew::start_app_in_element::<Waiting>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    assert_eq!(obtain_result(), "waiting");
+    assert_eq!(POLLED.load(Ordering::Relaxed), 1);
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+    app.destroy();
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 2);
+}
@@ -587,3 +587,135 @@ async fn effects_not_run_when_suspended() {
     );
     assert_eq!(*counter.borrow(), 4); // effects ran 4 times.
 }
+
+#[wasm_bindgen_test]
+async fn sibling_suspense_boundaries_resolve_independently() {
+    #[derive(PartialEq)]
+    pub struct SleepState {
+        s: Suspension,
+    }
+
+    impl SleepState {
+        fn new(ms: u32) -> Self {
+            let (s, handle) = Suspension::new();
+
+            spawn_local(async move {
+                TimeoutFuture::new(ms).await;
+
+                handle.resume();
+            });
+
+            Self { s }
+        }
+    }
+
+    #[hook]
+    pub fn use_sleep(ms: u32) -> SuspensionResult<()> {
+        let sleep_state = use_state(|| SleepState::new(ms));
+
+        if sleep_state.s.resumed() {
+            Ok(())
+        } else {
+            Err(sleep_state.s.clone())
+        }
+    }
+
+    #[derive(PartialEq, Properties)]
+    struct ContentProps {
+        name: &'static str,
+        ms: u32,
+    }
+
+    #[function_component(Content)]
+    fn content(props: &ContentProps) -> HtmlResult {
+        use_sleep(props.ms)?;
+
+        Ok(html! { <div>{ props.name }</div> })
+    }
+
+    #[function_component(App)]
+    fn app() -> Html {
+        html! {
+            <div id="result">
+                <Suspense fallback={html! { <div>{"wait...(fast)"}</div> }}>
+                    <Content name="fast" ms={30} />
+                </Suspense>
+                <Suspense fallback={html! { <div>{"wait...(slow)"}</div> }}>
+                    <Content name="slow" ms={150} />
+                </Suspense>
+            </div>
+        }
+    }
+
+    yew::start_app_in_element::<App>(gloo_utils::document().get_element_by_id("output").unwrap());
+
+    TimeoutFuture::new(10).await;
+    let result = obtain_result();
+    assert_eq!(
+        result.as_str(),
+        "<div>wait...(fast)</div><div>wait...(slow)</div>"
+    );
+
+    // The fast boundary does not wait for the slow one
+    TimeoutFuture::new(60).await;
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "<div>fast</div><div>wait...(slow)</div>");
+
+    TimeoutFuture::new(150).await;
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "<div>fast</div><div>slow</div>");
+}
+
+#[wasm_bindgen_test]
+async fn struct_component_suspends_in_view() {
+    struct Expensive {
+        computed: Suspension,
+    }
+
+    impl Component for Expensive {
+        type Message = ();
+        type Properties = ();
+
+        fn create(_ctx: &Context<Self>) -> Self {
+            // Stands in for a view, which is computed in the background
+            let computed = Suspension::from_future(async {
+                TimeoutFuture::new(50).await;
+            });
+            Self { computed }
+        }
+
+        fn view(&self, _ctx: &Context<Self>) -> Html {
+            html! { <div>{"computed"}</div> }
+        }
+
+        fn try_view(&self, ctx: &Context<Self>) -> HtmlResult {
+            if !self.computed.resumed() {
+                return Err(self.computed.clone().into());
+            }
+            Ok(self.view(ctx))
+        }
+    }
+
+    #[function_component(App)]
+    fn app() -> Html {
+        let fallback = html! {<div>{"wait..."}</div>};
+
+        html! {
+            <div id="result">
+                <Suspense {fallback}>
+                    <Expensive />
+                </Suspense>
+            </div>
+        }
+    }
+
+    yew::start_app_in_element::<App>(gloo_utils::document().get_element_by_id("output").unwrap());
+
+    TimeoutFuture::new(10).await;
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "<div>wait...</div>");
+
+    TimeoutFuture::new(60).await;
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "<div>computed</div>");
+}
@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn use_after_paint_works() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[function_component(UseAfterPaintComponent)]
+    fn use_after_paint_comp() -> Html {
+        use_after_paint(|| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+        });
+
+        html! {
+            <div id="result">{ "painted" }</div>
+        }
+    }
+
+    let _handle = yew::start_app_in_element::<UseAfterPaintComponent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+
+    sleep(Duration::ZERO).await;
+    // Rendered, but no frame has passed yet
+    assert_eq!(CALLS.load(Ordering::Relaxed), 0);
+
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+}
+
+#[wasm_bindgen_test]
+async fn use_after_paint_cancelled_on_destroy() {
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    #[function_component(UseAfterPaintComponent)]
+    fn use_after_paint_comp() -> Html {
+        use_after_paint(|| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+        });
+
+        html! {}
+    }
+
+    let handle = yew::start_app_in_element::<UseAfterPaintComponent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    handle.destroy();
+
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(CALLS.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod common;
+
+use common::obtain_result;
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn callback_equality() {
+    let callback = Callback::from(|value: u32| value + 1);
+    assert_eq!(callback, callback.clone());
+
+    // Equal closures wrapped separately don't share their identity
+    let rebuilt = Callback::from(|value: u32| value + 1);
+    assert_ne!(callback, rebuilt);
+}
+
+#[wasm_bindgen_test]
+async fn use_callback_works() {
+    static CHILD_RENDERS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Properties, PartialEq)]
+    struct Props {
+        callback: Callback<String, String>,
+    }
+
+    #[function_component(MyComponent)]
+    fn my_component(props: &Props) -> Html {
+        CHILD_RENDERS.fetch_add(1, Ordering::Relaxed);
+        let greeting = props.callback.emit("Yew".to_string());
+
+        html! {
+            <>{ greeting }</>
+        }
+    }
+
+    #[function_component(UseCallbackComponent)]
+    fn use_callback_comp() -> Html {
+        let state = use_state(|| 0);
+
+        let callback = use_callback(move |name, _| format!("Hello, {}!", name), ());
+
+        use_effect(move || {
+            if *state < 5 {
+                state.set(*state + 1);
+            }
+
+            || {}
+        });
+
+        html! {
+            <div>
+                {"The test output is: "}
+                <div id="result"><MyComponent {callback} /></div>
+                {"\n"}
+            </div>
+        }
+    }
+
+    yew::start_app_in_element::<UseCallbackComponent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+
+    sleep(Duration::ZERO).await;
+
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "Hello, Yew!");
+    // The parent re-rendered several times, but the callback kept its identity
+    assert_eq!(CHILD_RENDERS.load(Ordering::Relaxed), 1);
+}
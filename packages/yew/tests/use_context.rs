@@ -263,3 +263,152 @@ async fn use_context_update_works() {
         "current: hello world!, total: 4"
     );
 }
+
+#[wasm_bindgen_test]
+async fn struct_component_context_works() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Theme(&'static str);
+
+    enum Msg {
+        ThemeChanged(Theme),
+    }
+
+    struct Themed {
+        theme: Theme,
+        renders: usize,
+        _handle: ContextHandle<Theme>,
+    }
+
+    impl Component for Themed {
+        type Message = Msg;
+        type Properties = ();
+
+        fn create(ctx: &Context<Self>) -> Self {
+            let (theme, handle) = ctx
+                .link()
+                .context(ctx.link().callback(Msg::ThemeChanged))
+                .expect("no theme provided");
+
+            Self {
+                theme,
+                renders: 0,
+                _handle: handle,
+            }
+        }
+
+        fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+            match msg {
+                Msg::ThemeChanged(theme) => self.theme = theme,
+            }
+            true
+        }
+
+        fn view(&self, _ctx: &Context<Self>) -> Html {
+            html! { <div id="result">{ format!("{}, {}", self.theme.0, self.renders) }</div> }
+        }
+
+        fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+            self.renders += 1;
+        }
+    }
+
+    #[function_component]
+    fn App() -> Html {
+        let theme = use_state(|| Theme("light"));
+        {
+            let theme = theme.clone();
+            use_effect_with_deps(
+                move |_| {
+                    theme.set(Theme("dark"));
+                    || {}
+                },
+                (),
+            );
+        }
+
+        html! {
+            <ContextProvider<Theme> context={(*theme).clone()}>
+                <Themed />
+            </ContextProvider<Theme>>
+        }
+    }
+
+    yew::start_app_in_element::<App>(gloo_utils::document().get_element_by_id("output").unwrap());
+    sleep(Duration::ZERO).await;
+
+    // Rendered once with the initial context, then once more after it changed
+    assert_eq!(obtain_result_by_id("result"), "dark, 1");
+}
+
+#[wasm_bindgen_test]
+async fn use_context_selector_skips_unselected_changes() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct AppState {
+        name: String,
+        count: usize,
+    }
+
+    #[function_component]
+    fn NameOutlet() -> Html {
+        let counter = use_mut_ref(|| 0);
+        *counter.borrow_mut() += 1;
+
+        let name = use_context_selector(|state: &Rc<AppState>| state.name.clone())
+            .expect("context not passed down");
+
+        html! {
+            <div id="result">
+                { format!("current: {}, total: {}", name, counter.borrow()) }
+            </div>
+        }
+    }
+
+    #[function_component]
+    fn TestComponent() -> Html {
+        type AppStateProvider = ContextProvider<Rc<AppState>>;
+
+        let state = use_state(|| AppState {
+            name: "hello".into(),
+            count: 0,
+        });
+        let rendered = use_mut_ref(|| 0);
+        {
+            let state = state.clone();
+            use_effect(move || {
+                let count = *rendered.borrow();
+                let next = match count {
+                    0 | 2 => Some(AppState {
+                        count: state.count + 1,
+                        ..(*state).clone()
+                    }),
+                    1 => Some(AppState {
+                        name: "world".into(),
+                        ..(*state).clone()
+                    }),
+                    _ => None,
+                };
+                if let Some(next) = next {
+                    state.set(next);
+                    *rendered.borrow_mut() += 1;
+                }
+                || {}
+            });
+        }
+        html! {
+            <AppStateProvider context={Rc::new((*state).clone())}>
+                <div id="count">{ state.count }</div>
+                <NameOutlet />
+            </AppStateProvider>
+        }
+    }
+
+    yew::start_app_in_element::<TestComponent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+
+    sleep(Duration::ZERO).await;
+
+    assert_eq!(obtain_result_by_id("count"), "2");
+    // 1 initial render + 1 name change, the count changes are skipped
+    assert_eq!(obtain_result_by_id("result"), "current: world, total: 2");
+}
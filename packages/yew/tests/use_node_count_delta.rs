@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicIsize, Ordering};
+
+mod common;
+
+use common::obtain_result;
+use gloo::timers::future::sleep;
+use std::time::Duration;
+use wasm_bindgen_test::*;
+use yew::prelude::*;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+static DELTA: AtomicIsize = AtomicIsize::new(0);
+
+#[function_component]
+fn Pair() -> Html {
+    html! { <><i /><b /></> }
+}
+
+#[derive(Properties, PartialEq)]
+struct RowsProps {
+    pairs: usize,
+    // Re-renders the rows, even if the number of pairs did not change
+    generation: usize,
+}
+
+#[function_component]
+fn Rows(props: &RowsProps) -> Html {
+    let delta = use_node_count_delta();
+    use_effect(move || {
+        DELTA.store(delta.get(), Ordering::Relaxed);
+        || {}
+    });
+
+    html! {
+        <>
+            { for (0..props.pairs).map(|_| html! { <Pair /> }) }
+        </>
+    }
+}
+
+struct App {
+    pairs: usize,
+    generation: usize,
+}
+
+impl Component for App {
+    type Message = usize;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            pairs: 2,
+            generation: 0,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        self.pairs = msg;
+        self.generation += 1;
+        true
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <div id="result">
+                <Rows pairs={self.pairs} generation={self.generation} />
+            </div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn reports_node_count_delta_including_components() {
+    let app = yew::start_app_in_element::<App>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    assert_eq!(obtain_result(), "<i></i><b></b><i></i><b></b>");
+    assert_eq!(DELTA.load(Ordering::Relaxed), 4);
+
+    app.send_message(3);
+    sleep(Duration::ZERO).await;
+    assert_eq!(DELTA.load(Ordering::Relaxed), 2);
+
+    app.send_message(3);
+    sleep(Duration::ZERO).await;
+    assert_eq!(DELTA.load(Ordering::Relaxed), 0);
+
+    app.send_message(0);
+    sleep(Duration::ZERO).await;
+    assert_eq!(DELTA.load(Ordering::Relaxed), -6);
+}
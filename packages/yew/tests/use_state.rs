@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 mod common;
 
 use common::obtain_result;
@@ -33,6 +35,54 @@ async fn use_state_works() {
     assert_eq!(result.as_str(), "5");
 }
 
+#[wasm_bindgen_test]
+async fn use_state_init_runs_once() {
+    static INITS: AtomicUsize = AtomicUsize::new(0);
+
+    fn expensive_init() -> u32 {
+        INITS.fetch_add(1, Ordering::Relaxed);
+        0
+    }
+
+    #[function_component(UseComponent)]
+    fn use_state_comp() -> Html {
+        // The initializers are only called on the first render
+        let counter = use_state(expensive_init);
+        let reducer_counter = use_reducer(|| UseStateReducer(expensive_init()));
+        let memo = use_memo(|_| expensive_init(), ());
+        if *counter < 5 {
+            counter.set(*counter + 1);
+            reducer_counter.dispatch(());
+        }
+        html! {
+            <div>
+                {"Test Output: "}
+                <div id="result">{*counter + reducer_counter.0 + *memo}</div>
+                {"\n"}
+            </div>
+        }
+    }
+
+    #[derive(PartialEq)]
+    struct UseStateReducer(u32);
+
+    impl Reducible for UseStateReducer {
+        type Action = ();
+
+        fn reduce(self: std::rc::Rc<Self>, _action: ()) -> std::rc::Rc<Self> {
+            UseStateReducer(self.0 + 1).into()
+        }
+    }
+
+    yew::start_app_in_element::<UseComponent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "10");
+    assert_eq!(INITS.load(Ordering::Relaxed), 3);
+}
+
 #[wasm_bindgen_test]
 async fn multiple_use_state_setters() {
     #[function_component(UseComponent)]
@@ -75,6 +125,41 @@ async fn multiple_use_state_setters() {
     assert_eq!(result.as_str(), "11");
 }
 
+#[wasm_bindgen_test]
+async fn use_state_functional_updates() {
+    #[function_component(UseComponent)]
+    fn use_state_comp() -> Html {
+        let counter = use_state(|| 0);
+        {
+            let counter = counter.clone();
+            use_effect_with_deps(
+                move |_| {
+                    // Both updates are dispatched from the same stale handle.
+                    counter.set_with(|prev| prev + 1);
+                    counter.set_with(|prev| prev + 1);
+                    || {}
+                },
+                (),
+            );
+        }
+
+        html! {
+            <div>
+                {"Test Output: "}
+                <div id="result">{*counter}</div>
+                {"\n"}
+            </div>
+        }
+    }
+
+    yew::start_app_in_element::<UseComponent>(
+        gloo_utils::document().get_element_by_id("output").unwrap(),
+    );
+    sleep(Duration::ZERO).await;
+    let result = obtain_result();
+    assert_eq!(result.as_str(), "2");
+}
+
 #[wasm_bindgen_test]
 async fn use_state_eq_works() {
     use std::sync::atomic::{AtomicUsize, Ordering};